@@ -24,52 +24,18 @@ extern crate rand;
 use rsgenetic::sim::*;
 use rsgenetic::sim::seq::Simulator;
 use rsgenetic::sim::select::*;
+use rsgenetic::sim::types::F64;
 use rsgenetic::pheno::*;
 use rand::distributions::{IndependentSample, Range};
-use std::cmp::Ordering;
-
-struct MyFitness {
-    f: f64,
-}
-
-impl Eq for MyFitness {}
-
-impl PartialEq for MyFitness {
-    fn eq(&self, other: &MyFitness) -> bool {
-        (self.f - other.f).abs() < 0.0001
-    }
-}
-
-impl PartialOrd for MyFitness {
-    fn partial_cmp(&self, other: &MyFitness) -> Option<Ordering> {
-        self.f.partial_cmp(&other.f)
-    }
-}
-
-impl Ord for MyFitness {
-    fn cmp(&self, other: &MyFitness) -> Ordering {
-        self.partial_cmp(other).unwrap_or(Ordering::Equal)
-    }
-}
-
-impl Fitness for MyFitness {
-    fn zero() -> MyFitness {
-        MyFitness { f: 0.0 }
-    }
-
-    fn abs_diff(&self, other: &MyFitness) -> MyFitness {
-        MyFitness { f: (self.f - other.f).abs() }
-    }
-}
 
 struct MyData {
     x: f64,
 }
 
-impl Phenotype<MyFitness> for MyData {
-    fn fitness(&self) -> MyFitness {
+impl Phenotype<F64> for MyData {
+    fn fitness(&self) -> F64 {
         // Calculate the function here, because it's what we wish to maximize.
-        MyFitness { f: 10.0 - ((self.x + 3.0) * (self.x + 3.0)) }
+        F64(10.0 - ((self.x + 3.0) * (self.x + 3.0)))
     }
 
     fn crossover(&self, other: &MyData) -> MyData {
@@ -108,11 +74,11 @@ fn main() {
         let result = s.get().unwrap();
         println!("Intermediate result: ({}, {}).",
                  result.x,
-                 result.fitness().f);
+                 result.fitness().value());
     }
     let result = s.get().unwrap();
     let time = s.time();
     println!("Execution time: {} ns.", time.unwrap());
     println!("Expected result: (-3, 10).");
-    println!("Result: ({}, {}).", result.x, result.fitness().f);
+    println!("Result: ({}, {}).", result.x, result.fitness().value());
 }
@@ -27,6 +27,7 @@ extern crate rand;
 use rsgenetic::sim::*;
 use rsgenetic::sim::seq::Simulator;
 use rsgenetic::sim::select::*;
+use rsgenetic::sim::init::FromFn;
 use rsgenetic::pheno::*;
 use rand::Rng;
 
@@ -109,17 +110,15 @@ impl Clone for LoadingScheme {
 }
 
 fn main() {
-    let mut population: Vec<LoadingScheme> = Vec::with_capacity(300);
-    let mut rng = ::rand::thread_rng();
-    for _ in 0..300 {
-        let mut pheno: Scheme = Vec::with_capacity(PACKAGES.len());
-        for j in 0..PACKAGES.len() {
-            let index = rng.gen::<usize>() % NUM_TRUCKS;
-            pheno.push((index, PACKAGES[j]));
-        }
-        population.push(LoadingScheme { scheme: pheno });
-    }
-    let mut s = Simulator::builder(&mut population)
+    // Assign every package to a random truck.
+    let random_scheme = |rng: &mut SimRng| {
+        let scheme = PACKAGES.iter()
+            .map(|&package| (rng.gen::<usize>() % NUM_TRUCKS, package))
+            .collect();
+        LoadingScheme { scheme }
+    };
+    let mut s = Simulator::owned_builder(Vec::new())
+                    .set_initializer(Box::new(FromFn::new(random_scheme)), 300)
                     .set_selector(Box::new(MaximizeSelector::new(10)))
                     .set_max_iters(100)
                     .build();
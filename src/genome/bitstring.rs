@@ -0,0 +1,452 @@
+// file: bitstring.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::{self, Rng};
+use sim::LocalRng;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use super::FitnessFn;
+
+const WORD_BITS: usize = 64;
+
+/// The crossover operator used by a `BitString`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitCrossover {
+    /// Take the bits before a random point from the first parent,
+    /// and the rest from the second parent.
+    OnePoint,
+    /// Take the bits between two random points from the second parent,
+    /// and the rest from the first parent.
+    TwoPoint,
+    /// Take every bit from either parent with equal probability.
+    Uniform,
+}
+
+/// The configuration shared by `BitString` genomes: their length, fitness function
+/// and genetic operators.
+///
+/// ```
+/// use rsgenetic::genome::{BitCrossover, BitStringConfig};
+///
+/// // Maximise the number of ones.
+/// let config = BitStringConfig::new(32, |b| b.count_ones() as i32)
+///     .set_crossover(BitCrossover::Uniform)
+///     .set_mutation_rate(1.0 / 32.0);
+/// let population: Vec<_> = (0..100).map(|_| config.random()).collect();
+/// ```
+pub struct BitStringConfig<F: Fitness> {
+    len: usize,
+    crossover: BitCrossover,
+    mutation_rate: f64,
+    fitness: FitnessFn<BitString<F>, F>,
+}
+
+impl<F: Fitness> BitStringConfig<F> {
+    /// Create a configuration for bit strings of `len` bits, of which the fitness
+    /// is computed by `fitness`.
+    ///
+    /// By default, one-point crossover is used, and every bit is flipped
+    /// with a probability of `1 / len` during mutation.
+    pub fn new<G>(len: usize, fitness: G) -> BitStringConfig<F>
+        where G: Fn(&BitString<F>) -> F + Send + Sync + 'static
+    {
+        BitStringConfig {
+            len,
+            crossover: BitCrossover::OnePoint,
+            mutation_rate: 1.0 / len.max(1) as f64,
+            fitness: Arc::new(fitness),
+        }
+    }
+
+    /// Set the crossover operator.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover(mut self, crossover: BitCrossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+    /// Set the probability that a single bit is flipped during mutation.
+    /// The rate is clamped to the range `[0, 1]`, and defaults to one over the length.
+    ///
+    /// This rate applies within `mutate`. A simulator first decides whether a child is
+    /// mutated at all with its own mutation rate, which defaults to `1` (see
+    /// `seq::SimulatorBuilder::set_mutation_rate`), so a bit of a child is mutated with
+    /// the product of both rates.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the number of bits of the genomes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the genomes have no bits at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Create a bit string of which all bits are zero.
+    pub fn zeros(&self) -> BitString<F> {
+        BitString {
+            words: vec![0; self.len.div_ceil(WORD_BITS)],
+            config: self.clone(),
+        }
+    }
+
+    /// Create a bit string of which every bit is chosen uniformly at random.
+    pub fn random(&self) -> BitString<F> {
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Create a bit string of which every bit is chosen uniformly at random,
+    /// using `rng` as the source of randomness.
+    pub fn random_with<R: Rng>(&self, rng: &mut R) -> BitString<F> {
+        let mut result = self.zeros();
+        for word in &mut result.words {
+            *word = rng.gen();
+        }
+        result.clear_unused();
+        result
+    }
+
+    /// Create a bit string from `bits`, which should contain exactly `len` values.
+    pub fn from_bits(&self, bits: &[bool]) -> BitString<F> {
+        assert_eq!(bits.len(), self.len, "Wrong number of bits for this configuration.");
+        let mut result = self.zeros();
+        for (i, &bit) in bits.iter().enumerate() {
+            result.set(i, bit);
+        }
+        result
+    }
+}
+
+impl<F: Fitness> Clone for BitStringConfig<F> {
+    fn clone(&self) -> BitStringConfig<F> {
+        BitStringConfig {
+            len: self.len,
+            crossover: self.crossover,
+            mutation_rate: self.mutation_rate,
+            fitness: self.fitness.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> fmt::Debug for BitStringConfig<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BitStringConfig")
+            .field("len", &self.len)
+            .field("crossover", &self.crossover)
+            .field("mutation_rate", &self.mutation_rate)
+            .finish()
+    }
+}
+
+/// A genome consisting of a fixed number of bits, packed into 64-bit words.
+///
+/// Create bit strings with a `BitStringConfig`. Two bit strings are equal
+/// if they contain the same bits, regardless of their configuration.
+pub struct BitString<F: Fitness> {
+    words: Vec<u64>,
+    config: BitStringConfig<F>,
+}
+
+impl<F: Fitness> BitString<F> {
+    /// Get the number of bits.
+    pub fn len(&self) -> usize {
+        self.config.len
+    }
+
+    /// Returns whether this bit string has no bits at all.
+    pub fn is_empty(&self) -> bool {
+        self.config.len == 0
+    }
+
+    /// Get the bit at position `i`.
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len(), "Bit index out of bounds.");
+        self.words[i / WORD_BITS] >> (i % WORD_BITS) & 1 == 1
+    }
+
+    /// Set the bit at position `i` to `bit`.
+    pub fn set(&mut self, i: usize, bit: bool) {
+        assert!(i < self.len(), "Bit index out of bounds.");
+        let mask = 1 << (i % WORD_BITS);
+        if bit {
+            self.words[i / WORD_BITS] |= mask;
+        } else {
+            self.words[i / WORD_BITS] &= !mask;
+        }
+    }
+
+    /// Flip the bit at position `i`.
+    pub fn flip(&mut self, i: usize) {
+        assert!(i < self.len(), "Bit index out of bounds.");
+        self.words[i / WORD_BITS] ^= 1 << (i % WORD_BITS);
+    }
+
+    /// Count the bits that are set.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Iterate over all bits, in order.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len()).map(move |i| self.get(i))
+    }
+
+    /// Get the packed bits. Bit `i` is stored in word `i / 64`, at position `i % 64`.
+    /// Unused bits of the last word are always zero.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Get the configuration of this bit string.
+    pub fn config(&self) -> &BitStringConfig<F> {
+        &self.config
+    }
+
+    /// Create a child that takes the bits for which `mask` is set from `other`,
+    /// and all other bits from `self`.
+    fn mix(&self, other: &BitString<F>, mask: &[u64]) -> BitString<F> {
+        let mut child = self.clone();
+        for ((word, &theirs), &m) in child.words.iter_mut().zip(&other.words).zip(mask) {
+            *word = (*word & !m) | (theirs & m);
+        }
+        child
+    }
+
+    /// Create a random mask for the crossover operator of the configuration,
+    /// of which the set bits are taken from the other parent.
+    fn crossover_mask(&self) -> Vec<u64> {
+        let len = self.len();
+        let mut rng = LocalRng::get();
+        match self.config.crossover {
+            BitCrossover::OnePoint => range_mask(len, rng.gen_range(1, len), len),
+            BitCrossover::TwoPoint => {
+                let a = rng.gen_range(0, len + 1);
+                let b = rng.gen_range(0, len + 1);
+                range_mask(len, a.min(b), a.max(b))
+            }
+            BitCrossover::Uniform => self.words.iter().map(|_| rng.gen()).collect(),
+        }
+    }
+
+    /// Make sure the unused bits of the last word are zero.
+    fn clear_unused(&mut self) {
+        let used = self.len() % WORD_BITS;
+        if used > 0 {
+            if let Some(last) = self.words.last_mut() {
+                *last &= (1 << used) - 1;
+            }
+        }
+    }
+}
+
+/// Create a mask, of `len` bits packed into words, in which the bits in `start..end` are set.
+fn range_mask(len: usize, start: usize, end: usize) -> Vec<u64> {
+    (0..len.div_ceil(WORD_BITS))
+        .map(|w| {
+            let (lo, hi) = (w * WORD_BITS, (w + 1) * WORD_BITS);
+            let (from, to) = (start.max(lo), end.min(hi));
+            if from >= to {
+                0
+            } else if to - from == WORD_BITS {
+                !0
+            } else {
+                ((1 << (to - from)) - 1) << (from - lo)
+            }
+        })
+        .collect()
+}
+
+impl<F: Fitness> Phenotype<F> for BitString<F> {
+    fn fitness(&self) -> F {
+        (self.config.fitness)(self)
+    }
+
+    fn crossover(&self, other: &BitString<F>) -> BitString<F> {
+        assert_eq!(self.len(), other.len(), "Cannot cross bit strings of different lengths.");
+        if self.len() < 2 {
+            return self.clone();
+        }
+        self.mix(other, &self.crossover_mask())
+    }
+
+    /// Creates two complementary children: the bits that the first child takes from
+    /// `other` are the bits that the second child takes from `self`.
+    fn crossover_many(&self, other: &BitString<F>) -> Vec<BitString<F>> {
+        assert_eq!(self.len(), other.len(), "Cannot cross bit strings of different lengths.");
+        if self.len() < 2 {
+            return vec![self.clone(), other.clone()];
+        }
+        let mask = self.crossover_mask();
+        vec![self.mix(other, &mask), other.mix(self, &mask)]
+    }
+
+    fn num_children(&self) -> usize {
+        2
+    }
+
+    fn mutate(&self) -> BitString<F> {
+        let mut child = self.clone();
+        let rate = self.config.mutation_rate;
+        if rate > 0.0 {
+            let mut rng = LocalRng::get();
+            for i in 0..child.len() {
+                if rng.gen::<f64>() < rate {
+                    child.flip(i);
+                }
+            }
+        }
+        child
+    }
+}
+
+impl<F: Fitness> Clone for BitString<F> {
+    fn clone(&self) -> BitString<F> {
+        BitString {
+            words: self.words.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> PartialEq for BitString<F> {
+    fn eq(&self, other: &BitString<F>) -> bool {
+        self.len() == other.len() && self.words == other.words
+    }
+}
+
+impl<F: Fitness> Eq for BitString<F> {}
+
+impl<F: Fitness> Hash for BitString<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        self.words.hash(state);
+    }
+}
+
+impl<F: Fitness> fmt::Debug for BitString<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bits: String = self.iter().map(|b| if b { '1' } else { '0' }).collect();
+        f.debug_struct("BitString").field("bits", &bits).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::range_mask;
+    use genome::*;
+    use pheno::Phenotype;
+    use sim::*;
+    use sim::select::TournamentSelector;
+
+    fn ones(len: usize) -> BitStringConfig<i64> {
+        BitStringConfig::new(len, |b| b.count_ones() as i64)
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut b = ones(100).zeros();
+        b.set(3, true);
+        b.set(70, true);
+        b.flip(99);
+        b.flip(3);
+        assert!(!b.get(3));
+        assert!(b.get(70));
+        assert!(b.get(99));
+        assert_eq!(b.count_ones(), 2);
+        assert_eq!(b.fitness(), 2);
+    }
+
+    #[test]
+    fn test_random_clears_unused_bits() {
+        let b = ones(70).random();
+        assert_eq!(b.words().len(), 2);
+        assert_eq!(b.words()[1] >> 6, 0);
+    }
+
+    #[test]
+    fn test_range_mask() {
+        assert_eq!(range_mask(70, 2, 5), vec![0b11100, 0]);
+        assert_eq!(range_mask(70, 60, 66), vec![0xf << 60, 0b11]);
+        assert_eq!(range_mask(128, 0, 128), vec![!0, !0]);
+        assert_eq!(range_mask(70, 5, 5), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_crossover() {
+        for &crossover in &[BitCrossover::OnePoint, BitCrossover::TwoPoint, BitCrossover::Uniform] {
+            let config = ones(150).set_crossover(crossover);
+            let a = config.zeros();
+            let b = config.from_bits(&[true; 150]);
+            for _ in 0..20 {
+                let child = a.crossover(&b);
+                assert_eq!(child.len(), 150);
+                if crossover == BitCrossover::OnePoint {
+                    // A prefix of zeros, followed by ones.
+                    let first = child.iter().position(|x| x).unwrap();
+                    assert!(first > 0);
+                    assert!(child.iter().skip(first).all(|x| x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_crossover_many() {
+        for &crossover in &[BitCrossover::OnePoint, BitCrossover::TwoPoint, BitCrossover::Uniform] {
+            let config = ones(150).set_crossover(crossover);
+            let a = config.zeros();
+            let b = config.from_bits(&[true; 150]);
+            let children = a.crossover_many(&b);
+            assert_eq!(children.len(), 2);
+            // Every bit comes from the other parent in the other child.
+            assert!(children[0].iter().zip(children[1].iter()).all(|(x, y)| x != y));
+        }
+    }
+
+    #[test]
+    fn test_mutation_rate() {
+        let zero = ones(100).set_mutation_rate(0.0).random();
+        assert_eq!(zero.mutate(), zero);
+        let all = ones(100).set_mutation_rate(1.0).zeros();
+        assert_eq!(all.mutate().count_ones(), 100);
+    }
+
+    #[test]
+    fn test_one_max() {
+        let config = ones(40).set_crossover(BitCrossover::Uniform);
+        let mut rng = SimRng::new(0);
+        let mut population: Vec<BitString<i64>> =
+            (0..50).map(|_| config.random_with(&mut rng)).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(20, 4)))
+            .set_elitism(1)
+            .set_max_iters(200)
+            .set_seed(0)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert!(s.get().unwrap().count_ones() >= 35);
+    }
+}
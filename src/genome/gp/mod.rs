@@ -0,0 +1,52 @@
+// file: mod.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Genetic programming: genomes that are expression trees.
+//!
+//! First, describe the building blocks of the trees in a `PrimitiveSet`: functions with
+//! a fixed arity, input variables, constants and ephemeral random constants. Then create
+//! a `TreeConfig` with the primitive set and a fitness function, which usually evaluates
+//! the tree on some data using `Tree::evaluate`.
+//!
+//! Trees are stored in prefix order, which keeps them compact and makes subtree operations
+//! cheap. The depth of a tree is the length of the longest path from its root to a leaf,
+//! so a tree consisting of a single terminal has depth zero.
+//!
+//! ```
+//! use rsgenetic::genome::gp::{PrimitiveSet, TreeConfig};
+//! use rsgenetic::sim::types::F64;
+//!
+//! // Find a formula for `x * x + x`.
+//! let primitives = PrimitiveSet::new()
+//!     .add_function("add", 2, |args: &[f64]| args[0] + args[1])
+//!     .add_function("mul", 2, |args: &[f64]| args[0] * args[1])
+//!     .add_variable("x")
+//!     .add_constant("one", 1.0);
+//! let config = TreeConfig::new(primitives, |tree| {
+//!     let error: f64 = (-10..10)
+//!         .map(|i| f64::from(i) / 2.0)
+//!         .map(|x| (tree.evaluate(&[x]) - (x * x + x)).abs())
+//!         .sum();
+//!     F64(-error)
+//! });
+//! let population = config.ramped_half_and_half(100);
+//! ```
+
+mod primitives;
+mod tree;
+
+pub use self::primitives::{Node, PrimitiveSet};
+pub use self::tree::{Tree, TreeConfig, TreeMutation};
@@ -0,0 +1,200 @@
+// file: primitives.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rand::Rng;
+use sim::SimRng;
+use std::fmt;
+use std::sync::Arc;
+
+/// A node of an expression tree.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node<V> {
+    /// The function with the given index in the `PrimitiveSet`.
+    Function(usize),
+    /// The input variable with the given index.
+    Variable(usize),
+    /// A constant value.
+    Constant(V),
+}
+
+/// The implementation of a function in an expression tree.
+type ApplyFn<V> = Arc<dyn Fn(&[V]) -> V + Send + Sync>;
+
+/// The generator of an ephemeral random constant.
+type GenerateFn<V> = Arc<dyn Fn(&mut SimRng) -> V + Send + Sync>;
+
+/// A function that can be used in an expression tree.
+struct Function<V> {
+    name: String,
+    arity: usize,
+    apply: ApplyFn<V>,
+}
+
+/// A leaf that can be used in an expression tree.
+enum Terminal<V> {
+    /// The input variable with the given index.
+    Variable(usize),
+    /// A constant value.
+    Constant(V),
+    /// A constant of which the value is generated when the leaf is created.
+    Ephemeral(GenerateFn<V>),
+}
+
+/// The building blocks of expression trees: functions, which are the inner nodes,
+/// and terminals, which are the leaves.
+///
+/// Functions and terminals are identified by their names when a tree is printed.
+pub struct PrimitiveSet<V> {
+    functions: Vec<Function<V>>,
+    terminals: Vec<(String, Terminal<V>)>,
+    variables: Vec<String>,
+}
+
+impl<V> PrimitiveSet<V>
+    where V: Clone + fmt::Debug + Send + Sync + 'static
+{
+    /// Create an empty primitive set.
+    pub fn new() -> PrimitiveSet<V> {
+        PrimitiveSet {
+            functions: Vec::new(),
+            terminals: Vec::new(),
+            variables: Vec::new(),
+        }
+    }
+
+    /// Add a function called `name`, that takes `arity` arguments and computes
+    /// its result with `apply`. The arity should be larger than zero: use terminals
+    /// for values without arguments.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn add_function<G>(mut self, name: &str, arity: usize, apply: G) -> Self
+        where G: Fn(&[V]) -> V + Send + Sync + 'static
+    {
+        assert!(arity > 0, "Functions should take at least one argument.");
+        self.functions.push(Function {
+            name: name.to_string(),
+            arity,
+            apply: Arc::new(apply),
+        });
+        self
+    }
+
+    /// Add an input variable called `name`. Variables are numbered in the order in which
+    /// they are added, which is the order in which their values are passed to `Tree::evaluate`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn add_variable(mut self, name: &str) -> Self {
+        let index = self.variables.len();
+        self.variables.push(name.to_string());
+        self.terminals.push((name.to_string(), Terminal::Variable(index)));
+        self
+    }
+
+    /// Add a constant called `name` with the given `value`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn add_constant(mut self, name: &str, value: V) -> Self {
+        self.terminals.push((name.to_string(), Terminal::Constant(value)));
+        self
+    }
+
+    /// Add an ephemeral random constant called `name`. Every time this terminal
+    /// is placed in a tree, `generate` is called to choose its value, which then
+    /// stays fixed. `generate` should draw from the random number generator it is
+    /// given, so that seeded runs are reproducible.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn add_ephemeral<G>(mut self, name: &str, generate: G) -> Self
+        where G: Fn(&mut SimRng) -> V + Send + Sync + 'static
+    {
+        self.terminals.push((name.to_string(), Terminal::Ephemeral(Arc::new(generate))));
+        self
+    }
+
+    /// Get the number of functions.
+    pub fn num_functions(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Get the number of terminals: variables, constants and ephemeral constants.
+    pub fn num_terminals(&self) -> usize {
+        self.terminals.len()
+    }
+
+    /// Get the number of input variables.
+    pub fn num_variables(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// Get the number of children of `node`.
+    pub fn arity(&self, node: &Node<V>) -> usize {
+        match *node {
+            Node::Function(i) => self.functions[i].arity,
+            _ => 0,
+        }
+    }
+
+    /// Get the indices of all functions with the given arity.
+    pub(super) fn functions_with_arity(&self, arity: usize) -> Vec<usize> {
+        (0..self.functions.len()).filter(|&i| self.functions[i].arity == arity).collect()
+    }
+
+    /// Create a leaf node from the terminal with index `i`, using `rng` to generate
+    /// the value of an ephemeral constant.
+    pub(super) fn terminal<R: Rng>(&self, i: usize, rng: &mut R) -> Node<V> {
+        match self.terminals[i].1 {
+            Terminal::Variable(index) => Node::Variable(index),
+            Terminal::Constant(ref value) => Node::Constant(value.clone()),
+            Terminal::Ephemeral(ref generate) => Node::Constant(generate(&mut rng.gen())),
+        }
+    }
+
+    /// Apply the function with index `i` to `args`.
+    pub(super) fn apply(&self, i: usize, args: &[V]) -> V {
+        (self.functions[i].apply)(args)
+    }
+
+    /// Get the name of `node`, or its value if it is a constant.
+    pub(super) fn describe(&self, node: &Node<V>) -> String {
+        match *node {
+            Node::Function(i) => self.functions[i].name.clone(),
+            Node::Variable(i) => self.variables[i].clone(),
+            Node::Constant(ref value) => format!("{:?}", value),
+        }
+    }
+}
+
+impl<V> Default for PrimitiveSet<V>
+    where V: Clone + fmt::Debug + Send + Sync + 'static
+{
+    fn default() -> PrimitiveSet<V> {
+        PrimitiveSet::new()
+    }
+}
+
+impl<V> fmt::Debug for PrimitiveSet<V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let functions: Vec<(&str, usize)> = self.functions
+            .iter()
+            .map(|x| (x.name.as_str(), x.arity))
+            .collect();
+        let terminals: Vec<&str> = self.terminals.iter().map(|x| x.0.as_str()).collect();
+        f.debug_struct("PrimitiveSet")
+            .field("functions", &functions)
+            .field("terminals", &terminals)
+            .finish()
+    }
+}
@@ -0,0 +1,644 @@
+// file: tree.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::{self, Rng};
+use sim::LocalRng;
+use std::fmt;
+use std::sync::Arc;
+use genome::FitnessFn;
+use super::primitives::{Node, PrimitiveSet};
+
+/// The mutation operator used by a `Tree`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeMutation {
+    /// Replace a random subtree by a newly grown subtree.
+    Subtree,
+    /// Replace a random node by another primitive with the same arity.
+    Point,
+    /// Replace a random subtree by one of its own subtrees. This always shrinks the tree,
+    /// which counters bloat.
+    Hoist,
+}
+
+/// The configuration shared by `Tree` genomes: their primitive set, fitness function,
+/// size limits and genetic operators.
+///
+/// Offspring that would exceed the maximum depth or size are rejected: the first parent
+/// (for crossover) or the original tree (for mutation) is returned instead. This keeps
+/// bloat under control.
+pub struct TreeConfig<V, F: Fitness> {
+    primitives: Arc<PrimitiveSet<V>>,
+    fitness: FitnessFn<Tree<V, F>, F>,
+    init_depth: (usize, usize),
+    max_depth: usize,
+    max_size: usize,
+    mutation: TreeMutation,
+    mutation_rate: f64,
+}
+
+impl<V, F> TreeConfig<V, F>
+    where V: Clone + fmt::Debug + Send + Sync + 'static,
+          F: Fitness
+{
+    /// Create a configuration for trees built from `primitives`, of which the fitness
+    /// is computed by `fitness`. The primitive set should contain at least one terminal.
+    ///
+    /// By default, initial trees have a depth between 2 and 6, offspring have a depth
+    /// of at most 17 and at most 1000 nodes, and 10% of the children undergo subtree mutation.
+    pub fn new<G>(primitives: PrimitiveSet<V>, fitness: G) -> TreeConfig<V, F>
+        where G: Fn(&Tree<V, F>) -> F + Send + Sync + 'static
+    {
+        assert!(primitives.num_terminals() > 0,
+                "The primitive set should contain at least one terminal.");
+        TreeConfig {
+            primitives: Arc::new(primitives),
+            fitness: Arc::new(fitness),
+            init_depth: (2, 6),
+            max_depth: 17,
+            max_size: 1000,
+            mutation: TreeMutation::Subtree,
+            mutation_rate: 0.1,
+        }
+    }
+
+    /// Set the range of depths of newly created trees, both inclusive.
+    /// Subtree mutation grows subtrees of at most depth `max`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_init_depth(mut self, min: usize, max: usize) -> Self {
+        assert!(min <= max, "The minimum depth should not exceed the maximum depth.");
+        self.init_depth = (min, max);
+        self
+    }
+
+    /// Set the maximum depth of offspring.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Set the maximum number of nodes of offspring.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_max_size(mut self, size: usize) -> Self {
+        self.max_size = size;
+        self
+    }
+
+    /// Set the mutation operator.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation(mut self, mutation: TreeMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+
+    /// Set the probability that a tree is mutated at all during mutation.
+    /// The rate is clamped to the range `[0, 1]`, and defaults to `0.1`.
+    ///
+    /// A simulator first decides whether `mutate` is called at all with its own mutation
+    /// rate, which defaults to `1` (see `seq::SimulatorBuilder::set_mutation_rate`), so a
+    /// child is mutated with the product of both rates. Set only one of them.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the primitive set.
+    pub fn primitives(&self) -> &PrimitiveSet<V> {
+        &self.primitives
+    }
+
+    /// Create `n` trees using ramped half-and-half initialisation: the trees are spread
+    /// evenly over the range of initial depths, and for every depth, half of the trees
+    /// are created with the *full* method and half with the *grow* method.
+    pub fn ramped_half_and_half(&self, n: usize) -> Vec<Tree<V, F>> {
+        self.ramped_half_and_half_with(&mut rand::thread_rng(), n)
+    }
+
+    /// Create `n` trees using ramped half-and-half initialisation,
+    /// using `rng` as the source of randomness.
+    pub fn ramped_half_and_half_with<R: Rng>(&self, rng: &mut R, n: usize) -> Vec<Tree<V, F>> {
+        let (min, max) = self.init_depth;
+        (0..n)
+            .map(|i| {
+                let depth = min + (i / 2) % (max - min + 1);
+                self.generate_tree(rng, depth, i % 2 == 0)
+            })
+            .collect()
+    }
+
+    /// Create a tree with a random initial depth, using either the *full* or the *grow*
+    /// method with equal probability.
+    pub fn random(&self) -> Tree<V, F> {
+        let mut rng = rand::thread_rng();
+        let depth = rng.gen_range(self.init_depth.0, self.init_depth.1 + 1);
+        let full = rng.gen();
+        self.generate_tree(&mut rng, depth, full)
+    }
+
+    /// Create a tree using the *full* method: every leaf is at depth `depth`,
+    /// unless the primitive set contains no functions.
+    pub fn full(&self, depth: usize) -> Tree<V, F> {
+        self.generate_tree(&mut rand::thread_rng(), depth, true)
+    }
+
+    /// Create a tree using the *grow* method: the tree has a depth of at most `depth`.
+    pub fn grow(&self, depth: usize) -> Tree<V, F> {
+        self.generate_tree(&mut rand::thread_rng(), depth, false)
+    }
+
+    /// Create a tree from `nodes`, in prefix order.
+    pub fn from_nodes(&self, nodes: Vec<Node<V>>) -> Tree<V, F> {
+        let tree = Tree {
+            nodes,
+            config: self.clone(),
+        };
+        assert!(tree.is_valid(), "The nodes do not form a single tree.");
+        tree
+    }
+
+    fn generate_tree<R: Rng>(&self, rng: &mut R, depth: usize, full: bool) -> Tree<V, F> {
+        let mut nodes = Vec::new();
+        self.generate(rng, depth, full, &mut nodes);
+        Tree {
+            nodes,
+            config: self.clone(),
+        }
+    }
+
+    /// Append a randomly generated subtree of at most depth `depth` to `out`.
+    fn generate<R: Rng>(&self, rng: &mut R, depth: usize, full: bool, out: &mut Vec<Node<V>>) {
+        let p = &self.primitives;
+        let (functions, terminals) = (p.num_functions(), p.num_terminals());
+        let leaf = depth == 0 || functions == 0 ||
+                   (!full && rng.gen_range(0, functions + terminals) >= functions);
+        if leaf {
+            let i = rng.gen_range(0, terminals);
+            out.push(p.terminal(i, rng));
+        } else {
+            let node = Node::Function(rng.gen_range(0, functions));
+            let arity = p.arity(&node);
+            out.push(node);
+            for _ in 0..arity {
+                self.generate(rng, depth - 1, full, out);
+            }
+        }
+    }
+}
+
+impl<V, F: Fitness> Clone for TreeConfig<V, F> {
+    fn clone(&self) -> TreeConfig<V, F> {
+        TreeConfig {
+            primitives: self.primitives.clone(),
+            fitness: self.fitness.clone(),
+            init_depth: self.init_depth,
+            max_depth: self.max_depth,
+            max_size: self.max_size,
+            mutation: self.mutation,
+            mutation_rate: self.mutation_rate,
+        }
+    }
+}
+
+impl<V, F: Fitness> fmt::Debug for TreeConfig<V, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TreeConfig")
+            .field("primitives", &self.primitives)
+            .field("init_depth", &self.init_depth)
+            .field("max_depth", &self.max_depth)
+            .field("max_size", &self.max_size)
+            .field("mutation", &self.mutation)
+            .field("mutation_rate", &self.mutation_rate)
+            .finish()
+    }
+}
+
+/// A genome that is an expression tree, stored in prefix order.
+///
+/// Create trees with a `TreeConfig`. Trees print as S-expressions,
+/// for example `(add x (mul x x))`.
+pub struct Tree<V, F: Fitness> {
+    nodes: Vec<Node<V>>,
+    config: TreeConfig<V, F>,
+}
+
+impl<V, F> Tree<V, F>
+    where V: Clone + fmt::Debug + Send + Sync + 'static,
+          F: Fitness
+{
+    /// Evaluate the tree, using `inputs` as the values of the input variables.
+    pub fn evaluate(&self, inputs: &[V]) -> V {
+        assert!(inputs.len() >= self.config.primitives.num_variables(),
+                "Not enough inputs: {}.",
+                inputs.len());
+        self.evaluate_at(0, inputs).0
+    }
+
+    /// Get the nodes of the tree, in prefix order.
+    pub fn nodes(&self) -> &[Node<V>] {
+        &self.nodes
+    }
+
+    /// Get the number of nodes.
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.depth_at(0).0
+    }
+
+    /// Get the configuration of this tree.
+    pub fn config(&self) -> &TreeConfig<V, F> {
+        &self.config
+    }
+
+    /// Evaluate the subtree starting at `pos`, and return its value
+    /// together with the position right after the subtree.
+    fn evaluate_at(&self, pos: usize, inputs: &[V]) -> (V, usize) {
+        match self.nodes[pos] {
+            Node::Function(i) => {
+                let arity = self.config.primitives.arity(&self.nodes[pos]);
+                let mut args = Vec::with_capacity(arity);
+                let mut next = pos + 1;
+                for _ in 0..arity {
+                    let (value, end) = self.evaluate_at(next, inputs);
+                    args.push(value);
+                    next = end;
+                }
+                (self.config.primitives.apply(i, &args), next)
+            }
+            Node::Variable(i) => (inputs[i].clone(), pos + 1),
+            Node::Constant(ref value) => (value.clone(), pos + 1),
+        }
+    }
+
+    /// Get the depth of the subtree starting at `pos`, together with
+    /// the position right after the subtree.
+    fn depth_at(&self, pos: usize) -> (usize, usize) {
+        let arity = self.config.primitives.arity(&self.nodes[pos]);
+        let mut depth = 0;
+        let mut next = pos + 1;
+        for _ in 0..arity {
+            let (d, end) = self.depth_at(next);
+            depth = depth.max(d + 1);
+            next = end;
+        }
+        (depth, next)
+    }
+
+    /// Get the position right after the subtree starting at `pos`.
+    fn subtree_end(&self, pos: usize) -> usize {
+        let mut open = 1;
+        let mut i = pos;
+        while open > 0 {
+            open = open + self.config.primitives.arity(&self.nodes[i]) - 1;
+            i += 1;
+        }
+        i
+    }
+
+    /// Returns whether the nodes form exactly one tree of known primitives.
+    fn is_valid(&self) -> bool {
+        let p = &self.config.primitives;
+        let known = self.nodes.iter().all(|node| match *node {
+            Node::Function(i) => i < p.num_functions(),
+            Node::Variable(i) => i < p.num_variables(),
+            Node::Constant(_) => true,
+        });
+        if !known {
+            return false;
+        }
+        let mut open = 1usize;
+        for (i, node) in self.nodes.iter().enumerate() {
+            open = open + p.arity(node) - 1;
+            if open == 0 {
+                return i + 1 == self.nodes.len();
+            }
+        }
+        false
+    }
+
+    /// Choose a random node, preferring inner nodes with a probability of 90%,
+    /// as proposed by Koza.
+    fn crossover_point<R: Rng>(&self, rng: &mut R) -> usize {
+        let p = &self.config.primitives;
+        let inner: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| p.arity(&self.nodes[i]) > 0)
+            .collect();
+        if !inner.is_empty() && rng.gen::<f64>() < 0.9 {
+            inner[rng.gen_range(0, inner.len())]
+        } else {
+            let leaves: Vec<usize> = (0..self.nodes.len())
+                .filter(|&i| p.arity(&self.nodes[i]) == 0)
+                .collect();
+            leaves[rng.gen_range(0, leaves.len())]
+        }
+    }
+
+    /// Create a tree by replacing the subtree starting at `pos` with `replacement`.
+    /// If the result exceeds the limits of the configuration, a copy of `self` is returned.
+    fn replace_subtree(&self, pos: usize, replacement: &[Node<V>]) -> Tree<V, F> {
+        let end = self.subtree_end(pos);
+        let mut nodes = Vec::with_capacity(self.nodes.len() - (end - pos) + replacement.len());
+        nodes.extend_from_slice(&self.nodes[..pos]);
+        nodes.extend_from_slice(replacement);
+        nodes.extend_from_slice(&self.nodes[end..]);
+        let child = Tree {
+            nodes,
+            config: self.config.clone(),
+        };
+        if child.size() > self.config.max_size || child.depth() > self.config.max_depth {
+            self.clone()
+        } else {
+            child
+        }
+    }
+}
+
+impl<V, F> Phenotype<F> for Tree<V, F>
+    where V: Clone + fmt::Debug + Send + Sync + 'static,
+          F: Fitness
+{
+    fn fitness(&self) -> F {
+        (self.config.fitness)(self)
+    }
+
+    fn crossover(&self, other: &Tree<V, F>) -> Tree<V, F> {
+        let mut rng = LocalRng::get();
+        let a = self.crossover_point(&mut rng);
+        let b = other.crossover_point(&mut rng);
+        self.replace_subtree(a, &other.nodes[b..other.subtree_end(b)])
+    }
+
+    /// Creates two children by swapping the chosen subtrees of both parents.
+    fn crossover_many(&self, other: &Tree<V, F>) -> Vec<Tree<V, F>> {
+        let mut rng = LocalRng::get();
+        let a = self.crossover_point(&mut rng);
+        let b = other.crossover_point(&mut rng);
+        vec![self.replace_subtree(a, &other.nodes[b..other.subtree_end(b)]),
+             other.replace_subtree(b, &self.nodes[a..self.subtree_end(a)])]
+    }
+
+    fn num_children(&self) -> usize {
+        2
+    }
+
+    fn mutate(&self) -> Tree<V, F> {
+        let mut rng = LocalRng::get();
+        if rng.gen::<f64>() >= self.config.mutation_rate {
+            return self.clone();
+        }
+        let pos = rng.gen_range(0, self.nodes.len());
+        match self.config.mutation {
+            TreeMutation::Subtree => {
+                let depth = rng.gen_range(0, self.config.init_depth.1 + 1);
+                let mut subtree = Vec::new();
+                self.config.generate(&mut rng, depth, false, &mut subtree);
+                self.replace_subtree(pos, &subtree)
+            }
+            TreeMutation::Point => {
+                let p = &self.config.primitives;
+                let node = match self.nodes[pos] {
+                    Node::Function(_) => {
+                        let arity = p.arity(&self.nodes[pos]);
+                        let candidates = p.functions_with_arity(arity);
+                        Node::Function(candidates[rng.gen_range(0, candidates.len())])
+                    }
+                    _ => {
+                        let i = rng.gen_range(0, p.num_terminals());
+                        p.terminal(i, &mut rng)
+                    }
+                };
+                let mut child = self.clone();
+                child.nodes[pos] = node;
+                child
+            }
+            TreeMutation::Hoist => {
+                let inner = rng.gen_range(pos, self.subtree_end(pos));
+                self.replace_subtree(pos, &self.nodes[inner..self.subtree_end(inner)])
+            }
+        }
+    }
+}
+
+impl<V: Clone, F: Fitness> Clone for Tree<V, F> {
+    fn clone(&self) -> Tree<V, F> {
+        Tree {
+            nodes: self.nodes.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<V: PartialEq, F: Fitness> PartialEq for Tree<V, F> {
+    fn eq(&self, other: &Tree<V, F>) -> bool {
+        self.nodes == other.nodes
+    }
+}
+
+impl<V, F> fmt::Display for Tree<V, F>
+    where V: Clone + fmt::Debug + Send + Sync + 'static,
+          F: Fitness
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Write the nodes in prefix order, closing parentheses when a function
+        // has received all of its arguments.
+        let p = &self.config.primitives;
+        let mut remaining: Vec<usize> = Vec::new();
+        for node in &self.nodes {
+            if !remaining.is_empty() {
+                write!(f, " ")?;
+            }
+            let arity = p.arity(node);
+            if arity > 0 {
+                write!(f, "({}", p.describe(node))?;
+                remaining.push(arity);
+                continue;
+            }
+            write!(f, "{}", p.describe(node))?;
+            while let Some(last) = remaining.pop() {
+                if last > 1 {
+                    remaining.push(last - 1);
+                    break;
+                }
+                write!(f, ")")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<V, F> fmt::Debug for Tree<V, F>
+    where V: Clone + fmt::Debug + Send + Sync + 'static,
+          F: Fitness
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tree").field("expression", &self.to_string()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genome::gp::*;
+    use pheno::Phenotype;
+    use rand::Rng;
+    use sim::*;
+    use sim::select::TournamentSelector;
+    use sim::types::F64;
+
+    fn primitives() -> PrimitiveSet<f64> {
+        PrimitiveSet::new()
+            .add_function("add", 2, |args: &[f64]| args[0] + args[1])
+            .add_function("mul", 2, |args: &[f64]| args[0] * args[1])
+            .add_function("neg", 1, |args: &[f64]| -args[0])
+            .add_variable("x")
+            .add_ephemeral("c", |rng| rng.gen_range(-1.0, 1.0))
+    }
+
+    /// Symbolic regression of `x * x + x`.
+    fn regression() -> TreeConfig<f64, F64> {
+        TreeConfig::new(primitives(), |tree| {
+            let error: f64 = (-10..10)
+                .map(|i| f64::from(i) / 2.0)
+                .map(|x| (tree.evaluate(&[x]) - (x * x + x)).abs())
+                .sum();
+            F64(-error)
+        })
+    }
+
+    fn example(config: &TreeConfig<f64, F64>) -> Tree<f64, F64> {
+        // (add x (mul (neg x) 2.0))
+        config.from_nodes(vec![Node::Function(0),
+                               Node::Variable(0),
+                               Node::Function(1),
+                               Node::Function(2),
+                               Node::Variable(0),
+                               Node::Constant(2.0)])
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let tree = example(&regression());
+        assert_eq!(tree.evaluate(&[3.0]), -3.0);
+        assert_eq!(tree.depth(), 3);
+        assert_eq!(tree.size(), 6);
+        assert_eq!(tree.to_string(), "(add x (mul (neg x) 2.0))");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_nodes_invalid() {
+        regression().from_nodes(vec![Node::Function(0), Node::Variable(0)]);
+    }
+
+    #[test]
+    fn test_ramped_half_and_half() {
+        let config = regression().set_init_depth(1, 4);
+        let trees = config.ramped_half_and_half(40);
+        assert_eq!(trees.len(), 40);
+        for (i, tree) in trees.iter().enumerate() {
+            let depth = 1 + (i / 2) % 4;
+            if i % 2 == 0 {
+                assert_eq!(tree.depth(), depth);
+            } else {
+                assert!(tree.depth() <= depth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ephemeral() {
+        let config = regression();
+        let trees = config.ramped_half_and_half_with(&mut SimRng::new(1), 50);
+        let constants: Vec<f64> = trees.iter()
+            .flat_map(|tree| tree.nodes().iter())
+            .filter_map(|node| match *node {
+                Node::Constant(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        assert!(constants.iter().all(|c| (-1.0..1.0).contains(c)));
+        assert!(constants.iter().any(|&c| c != constants[0]));
+        // The same seed generates the same constants.
+        assert!(config.ramped_half_and_half_with(&mut SimRng::new(1), 50) == trees);
+    }
+
+    #[test]
+    fn test_limits() {
+        let config = regression().set_max_depth(4).set_max_size(15).set_mutation_rate(1.0);
+        let trees = config.set_init_depth(2, 3).ramped_half_and_half(20);
+        for a in &trees {
+            for b in &trees {
+                let child = a.crossover(b).mutate();
+                assert!(child.depth() <= 4);
+                assert!(child.size() <= 15 || child == *a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crossover_many() {
+        let trees = regression().set_init_depth(2, 3).ramped_half_and_half(10);
+        for a in &trees {
+            for b in &trees {
+                let children = a.crossover_many(b);
+                assert_eq!(children.len(), 2);
+                // Swapping subtrees keeps the total number of nodes.
+                assert_eq!(children[0].size() + children[1].size(), a.size() + b.size());
+            }
+        }
+    }
+
+    #[test]
+    fn test_mutations() {
+        let config = regression().set_mutation_rate(1.0);
+        for &mutation in &[TreeMutation::Subtree, TreeMutation::Point, TreeMutation::Hoist] {
+            let config = config.clone().set_mutation(mutation);
+            let tree = example(&config);
+            for _ in 0..50 {
+                let child = tree.mutate();
+                let valid = config.from_nodes(child.nodes().to_vec());
+                match mutation {
+                    TreeMutation::Point => assert_eq!(valid.size(), tree.size()),
+                    TreeMutation::Hoist => assert!(valid.size() <= tree.size()),
+                    TreeMutation::Subtree => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_regression() {
+        let config = regression().set_mutation_rate(0.3);
+        let mut population = config.ramped_half_and_half_with(&mut SimRng::new(0), 100);
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(40, 5)))
+            .set_elitism(1)
+            .set_max_iters(300)
+            .set_seed(0)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert!(s.get().unwrap().fitness().value() > -1e-6);
+    }
+}
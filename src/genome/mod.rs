@@ -0,0 +1,43 @@
+// file: mod.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The genome module provides ready-made `Phenotype` implementations
+//! for common representations, together with their standard operators.
+//!
+//! Every genome type is created from a configuration, which holds the fitness function
+//! and the settings of the genetic operators. All genomes created from the same configuration
+//! share it, so you only need to provide a fitness closure to get a working `Phenotype`.
+//!
+//! The operators of these genomes draw their randomness from a generator that the simulator
+//! derives from its own random number generator while it creates children, so a seeded
+//! simulation with these genomes is reproducible. Outside of a simulator, they use a
+//! generator seeded from `rand::thread_rng`. To create reproducible individuals, pass a
+//! seeded generator to `random_with` or `TreeConfig::ramped_half_and_half_with`.
+
+use std::sync::Arc;
+
+pub mod gp;
+mod bitstring;
+mod real;
+mod permutation;
+
+pub use self::bitstring::{BitCrossover, BitString, BitStringConfig};
+pub use self::real::{RealCrossover, RealMutation, RealVector, RealVectorConfig};
+pub use self::permutation::{Permutation, PermutationConfig, PermutationCrossover,
+                            PermutationMutation};
+
+/// A fitness function shared by all genomes of type `G` created from the same configuration.
+type FitnessFn<G, F> = Arc<dyn Fn(&G) -> F + Send + Sync>;
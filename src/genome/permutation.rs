@@ -0,0 +1,631 @@
+// file: permutation.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::{self, Rng};
+use sim::LocalRng;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use super::FitnessFn;
+
+/// The crossover operator used by a `Permutation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermutationCrossover {
+    /// Partially mapped crossover (PMX): a random segment is taken from the first parent,
+    /// the other positions are filled from the second parent, resolving conflicts
+    /// through the mapping defined by the segment.
+    PartiallyMapped,
+    /// Order crossover (OX1): a random segment is taken from the first parent, the other
+    /// positions are filled with the remaining elements in the order of the second parent.
+    Order,
+    /// Cycle crossover: every element keeps the position it has in one of the parents.
+    Cycle,
+    /// Edge recombination: the child is built from the adjacencies of both parents,
+    /// treating them as closed tours. Well suited for routing problems.
+    EdgeRecombination,
+}
+
+/// The mutation operator used by a `Permutation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermutationMutation {
+    /// Swap the elements at two random positions.
+    Swap,
+    /// Move a random element to another random position.
+    Insert,
+    /// Reverse the order of a random segment.
+    Inversion,
+    /// Shuffle a random segment.
+    Scramble,
+}
+
+/// The configuration shared by `Permutation` genomes: their length, fitness function
+/// and genetic operators.
+///
+/// ```
+/// use rsgenetic::genome::{PermutationConfig, PermutationCrossover};
+///
+/// // Sort the elements in ascending order.
+/// let config = PermutationConfig::new(10, |p| {
+///         p.order().windows(2).filter(|w| w[0] < w[1]).count() as i32
+///     })
+///     .set_crossover(PermutationCrossover::Order);
+/// let population: Vec<_> = (0..100).map(|_| config.random()).collect();
+/// ```
+pub struct PermutationConfig<F: Fitness> {
+    len: usize,
+    crossover: PermutationCrossover,
+    mutation: PermutationMutation,
+    mutation_rate: f64,
+    fitness: FitnessFn<Permutation<F>, F>,
+}
+
+impl<F: Fitness> PermutationConfig<F> {
+    /// Create a configuration for permutations of the elements `0..len`,
+    /// of which the fitness is computed by `fitness`.
+    ///
+    /// By default, partially mapped crossover and swap mutation are used,
+    /// and every child is mutated.
+    pub fn new<G>(len: usize, fitness: G) -> PermutationConfig<F>
+        where G: Fn(&Permutation<F>) -> F + Send + Sync + 'static
+    {
+        PermutationConfig {
+            len,
+            crossover: PermutationCrossover::PartiallyMapped,
+            mutation: PermutationMutation::Swap,
+            mutation_rate: 1.0,
+            fitness: Arc::new(fitness),
+        }
+    }
+
+    /// Set the crossover operator.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover(mut self, crossover: PermutationCrossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+    /// Set the mutation operator.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation(mut self, mutation: PermutationMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+
+    /// Set the probability that a permutation is mutated at all during mutation.
+    /// The rate is clamped to the range `[0, 1]`, and defaults to `1`.
+    ///
+    /// A simulator first decides whether `mutate` is called at all with its own mutation
+    /// rate, which defaults to `1` (see `seq::SimulatorBuilder::set_mutation_rate`), so a
+    /// child is mutated with the product of both rates. Set only one of them.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the number of elements of the permutations.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the permutations have no elements at all.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Create the identity permutation.
+    pub fn identity(&self) -> Permutation<F> {
+        Permutation {
+            order: (0..self.len).collect(),
+            config: self.clone(),
+        }
+    }
+
+    /// Create a permutation chosen uniformly at random.
+    pub fn random(&self) -> Permutation<F> {
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Create a permutation chosen uniformly at random, using `rng` as the source of randomness.
+    pub fn random_with<R: Rng>(&self, rng: &mut R) -> Permutation<F> {
+        let mut result = self.identity();
+        rng.shuffle(&mut result.order);
+        result
+    }
+
+    /// Create a permutation from `order`, which should contain every element
+    /// of `0..len` exactly once.
+    pub fn from_order(&self, order: Vec<usize>) -> Permutation<F> {
+        assert!(is_permutation(&order, self.len),
+                "Not a permutation of 0..{}: {:?}.",
+                self.len,
+                order);
+        Permutation {
+            order,
+            config: self.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> Clone for PermutationConfig<F> {
+    fn clone(&self) -> PermutationConfig<F> {
+        PermutationConfig {
+            len: self.len,
+            crossover: self.crossover,
+            mutation: self.mutation,
+            mutation_rate: self.mutation_rate,
+            fitness: self.fitness.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> fmt::Debug for PermutationConfig<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PermutationConfig")
+            .field("len", &self.len)
+            .field("crossover", &self.crossover)
+            .field("mutation", &self.mutation)
+            .field("mutation_rate", &self.mutation_rate)
+            .finish()
+    }
+}
+
+/// A genome consisting of an ordering of the elements `0..len`.
+///
+/// Create permutations with a `PermutationConfig`. All operators produce valid
+/// permutations. Two permutations are equal if they have the same order,
+/// regardless of their configuration.
+pub struct Permutation<F: Fitness> {
+    order: Vec<usize>,
+    config: PermutationConfig<F>,
+}
+
+impl<F: Fitness> Permutation<F> {
+    /// Get the number of elements.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns whether this permutation has no elements at all.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Get the elements, in order.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Get the configuration of this permutation.
+    pub fn config(&self) -> &PermutationConfig<F> {
+        &self.config
+    }
+
+    /// Create a permutation with the same configuration from `order`.
+    fn with_order(&self, order: Vec<usize>) -> Permutation<F> {
+        debug_assert!(is_permutation(&order, self.len()));
+        Permutation {
+            order,
+            config: self.config.clone(),
+        }
+    }
+}
+
+/// Returns whether `order` contains every element of `0..len` exactly once.
+fn is_permutation(order: &[usize], len: usize) -> bool {
+    let mut seen = vec![false; len];
+    order.len() == len &&
+    order.iter().all(|&x| x < len && !std::mem::replace(&mut seen[x], true))
+}
+
+/// Get the position of every element in `order`.
+fn positions(order: &[usize]) -> Vec<usize> {
+    let mut result = vec![0; order.len()];
+    for (i, &x) in order.iter().enumerate() {
+        result[x] = i;
+    }
+    result
+}
+
+/// Choose a random segment `a..b` of a sequence of length `len`, with `a < b`.
+fn segment<R: Rng>(rng: &mut R, len: usize) -> (usize, usize) {
+    let a = rng.gen_range(0, len);
+    let b = rng.gen_range(0, len);
+    (a.min(b), a.max(b) + 1)
+}
+
+/// Partially mapped crossover, keeping the segment `a..b` of `p1`.
+fn pmx(p1: &[usize], p2: &[usize], a: usize, b: usize) -> Vec<usize> {
+    let len = p1.len();
+    let in_segment = |i: usize| i >= a && i < b;
+    let pos2 = positions(p2);
+    let mut child: Vec<Option<usize>> = vec![None; len];
+    let mut used = vec![false; len];
+    for (i, &x) in p1.iter().enumerate().take(b).skip(a) {
+        child[i] = Some(x);
+        used[x] = true;
+    }
+    for (i, &v) in p2.iter().enumerate().take(b).skip(a) {
+        if used[v] {
+            continue;
+        }
+        // Follow the mapping until a position outside of the segment is found.
+        let mut pos = i;
+        while in_segment(pos) {
+            pos = pos2[p1[pos]];
+        }
+        child[pos] = Some(v);
+        used[v] = true;
+    }
+    child.into_iter()
+        .enumerate()
+        .map(|(i, x)| x.unwrap_or(p2[i]))
+        .collect()
+}
+
+/// Order crossover, keeping the segment `a..b` of `p1`.
+fn order(p1: &[usize], p2: &[usize], a: usize, b: usize) -> Vec<usize> {
+    let len = p1.len();
+    let mut child = p1.to_vec();
+    let mut used = vec![false; len];
+    for &x in &p1[a..b] {
+        used[x] = true;
+    }
+    let mut remaining = (0..len).map(|k| p2[(b + k) % len]).filter(|&x| !used[x]);
+    for k in 0..(len - (b - a)) {
+        child[(b + k) % len] = remaining.next().unwrap();
+    }
+    child
+}
+
+/// Cycle crossover: the first cycle is taken from `p1`, the second from `p2`, and so on.
+fn cycle(p1: &[usize], p2: &[usize]) -> Vec<usize> {
+    let len = p1.len();
+    let pos1 = positions(p1);
+    let mut child = vec![0; len];
+    let mut visited = vec![false; len];
+    let mut from_first = true;
+    for start in 0..len {
+        if visited[start] {
+            continue;
+        }
+        let mut i = start;
+        while !visited[i] {
+            visited[i] = true;
+            child[i] = if from_first { p1[i] } else { p2[i] };
+            i = pos1[p2[i]];
+        }
+        from_first = !from_first;
+    }
+    child
+}
+
+/// Edge recombination crossover, treating both parents as closed tours.
+fn edge_recombination<R: Rng>(rng: &mut R, p1: &[usize], p2: &[usize]) -> Vec<usize> {
+    let len = p1.len();
+    let mut neighbours: Vec<Vec<usize>> = vec![Vec::with_capacity(4); len];
+    for parent in &[p1, p2] {
+        for i in 0..len {
+            let (x, next) = (parent[i], parent[(i + 1) % len]);
+            if x != next {
+                if !neighbours[x].contains(&next) {
+                    neighbours[x].push(next);
+                }
+                if !neighbours[next].contains(&x) {
+                    neighbours[next].push(x);
+                }
+            }
+        }
+    }
+    let mut child = Vec::with_capacity(len);
+    let mut visited = vec![false; len];
+    let mut current = p1[0];
+    loop {
+        child.push(current);
+        visited[current] = true;
+        for &n in &neighbours[current].clone() {
+            neighbours[n].retain(|&x| x != current);
+        }
+        if child.len() == len {
+            return child;
+        }
+        // Prefer the neighbour with the fewest remaining neighbours, breaking ties at random.
+        let candidates = &neighbours[current];
+        current = if candidates.is_empty() {
+            let unvisited: Vec<usize> = (0..len).filter(|&x| !visited[x]).collect();
+            unvisited[rng.gen_range(0, unvisited.len())]
+        } else {
+            let fewest = candidates.iter().map(|&n| neighbours[n].len()).min().unwrap();
+            let best: Vec<usize> = candidates.iter()
+                .cloned()
+                .filter(|&n| neighbours[n].len() == fewest)
+                .collect();
+            best[rng.gen_range(0, best.len())]
+        };
+    }
+}
+
+impl<F: Fitness> Phenotype<F> for Permutation<F> {
+    fn fitness(&self) -> F {
+        (self.config.fitness)(self)
+    }
+
+    fn crossover(&self, other: &Permutation<F>) -> Permutation<F> {
+        assert_eq!(self.len(), other.len(), "Cannot cross permutations of different lengths.");
+        if self.len() < 2 {
+            return self.clone();
+        }
+        let mut rng = LocalRng::get();
+        let (p1, p2) = (&self.order, &other.order);
+        let child = match self.config.crossover {
+            PermutationCrossover::PartiallyMapped => {
+                let (a, b) = segment(&mut rng, p1.len());
+                pmx(p1, p2, a, b)
+            }
+            PermutationCrossover::Order => {
+                let (a, b) = segment(&mut rng, p1.len());
+                order(p1, p2, a, b)
+            }
+            PermutationCrossover::Cycle => cycle(p1, p2),
+            PermutationCrossover::EdgeRecombination => edge_recombination(&mut rng, p1, p2),
+        };
+        self.with_order(child)
+    }
+
+    /// Creates two children by exchanging the roles of the parents, using the same
+    /// segment for partially mapped and order crossover. Edge recombination creates
+    /// a single child.
+    fn crossover_many(&self, other: &Permutation<F>) -> Vec<Permutation<F>> {
+        assert_eq!(self.len(), other.len(), "Cannot cross permutations of different lengths.");
+        if self.len() < 2 {
+            return (0..self.num_children())
+                .map(|i| if i % 2 == 0 { self.clone() } else { other.clone() })
+                .collect();
+        }
+        let mut rng = LocalRng::get();
+        let (p1, p2) = (&self.order, &other.order);
+        let (first, second) = match self.config.crossover {
+            PermutationCrossover::PartiallyMapped => {
+                let (a, b) = segment(&mut rng, p1.len());
+                (pmx(p1, p2, a, b), pmx(p2, p1, a, b))
+            }
+            PermutationCrossover::Order => {
+                let (a, b) = segment(&mut rng, p1.len());
+                (order(p1, p2, a, b), order(p2, p1, a, b))
+            }
+            PermutationCrossover::Cycle => (cycle(p1, p2), cycle(p2, p1)),
+            PermutationCrossover::EdgeRecombination => {
+                return vec![self.with_order(edge_recombination(&mut rng, p1, p2))];
+            }
+        };
+        vec![self.with_order(first), self.with_order(second)]
+    }
+
+    fn num_children(&self) -> usize {
+        match self.config.crossover {
+            PermutationCrossover::EdgeRecombination => 1,
+            _ => 2,
+        }
+    }
+
+    fn mutate(&self) -> Permutation<F> {
+        let mut rng = LocalRng::get();
+        let len = self.len();
+        if len < 2 || rng.gen::<f64>() >= self.config.mutation_rate {
+            return self.clone();
+        }
+        let mut child = self.order.clone();
+        match self.config.mutation {
+            PermutationMutation::Swap => {
+                let (i, j) = (rng.gen_range(0, len), rng.gen_range(0, len));
+                child.swap(i, j);
+            }
+            PermutationMutation::Insert => {
+                let x = child.remove(rng.gen_range(0, len));
+                child.insert(rng.gen_range(0, len), x);
+            }
+            PermutationMutation::Inversion => {
+                let (a, b) = segment(&mut rng, len);
+                child[a..b].reverse();
+            }
+            PermutationMutation::Scramble => {
+                let (a, b) = segment(&mut rng, len);
+                rng.shuffle(&mut child[a..b]);
+            }
+        }
+        self.with_order(child)
+    }
+}
+
+impl<F: Fitness> Clone for Permutation<F> {
+    fn clone(&self) -> Permutation<F> {
+        Permutation {
+            order: self.order.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> PartialEq for Permutation<F> {
+    fn eq(&self, other: &Permutation<F>) -> bool {
+        self.order == other.order
+    }
+}
+
+impl<F: Fitness> Eq for Permutation<F> {}
+
+impl<F: Fitness> Hash for Permutation<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.order.hash(state);
+    }
+}
+
+impl<F: Fitness> fmt::Debug for Permutation<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Permutation").field("order", &self.order).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cycle, is_permutation, order, pmx};
+    use genome::*;
+    use pheno::Phenotype;
+    use sim::*;
+    use sim::select::TournamentSelector;
+
+    /// Count the elements that are followed by a larger element.
+    fn sorted(len: usize) -> PermutationConfig<i64> {
+        PermutationConfig::new(len,
+                               |p| p.order().windows(2).filter(|w| w[0] < w[1]).count() as i64)
+    }
+
+    #[test]
+    fn test_is_permutation() {
+        assert!(is_permutation(&[2, 0, 1], 3));
+        assert!(!is_permutation(&[2, 0, 0], 3));
+        assert!(!is_permutation(&[0, 1, 3], 3));
+        assert!(!is_permutation(&[0, 1], 3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_order_invalid() {
+        sorted(3).from_order(vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn test_pmx() {
+        let p1: Vec<usize> = (0..9).collect();
+        let p2 = vec![8, 2, 6, 7, 1, 5, 4, 0, 3];
+        assert_eq!(pmx(&p1, &p2, 3, 7), vec![8, 2, 1, 3, 4, 5, 6, 0, 7]);
+    }
+
+    #[test]
+    fn test_order() {
+        let p1: Vec<usize> = (0..9).collect();
+        let p2 = vec![8, 2, 6, 7, 1, 5, 4, 0, 3];
+        assert_eq!(order(&p1, &p2, 3, 7), vec![2, 7, 1, 3, 4, 5, 6, 0, 8]);
+    }
+
+    #[test]
+    fn test_cycle() {
+        assert_eq!(cycle(&[0, 1, 2, 3], &[1, 0, 3, 2]), vec![0, 1, 3, 2]);
+        let p1 = vec![0, 1, 2, 3, 4, 5, 6, 7];
+        let p2 = vec![7, 4, 1, 0, 2, 5, 3, 6];
+        let child = cycle(&p1, &p2);
+        assert!(is_permutation(&child, 8));
+        assert!((0..8).all(|i| child[i] == p1[i] || child[i] == p2[i]));
+    }
+
+    #[test]
+    fn test_edge_recombination_keeps_edges() {
+        let config = sorted(20).set_crossover(PermutationCrossover::EdgeRecombination);
+        let parent = config.random();
+        let len = parent.len();
+        let is_edge = |a: usize, b: usize| {
+            (0..len).any(|i| {
+                let (x, y) = (parent.order()[i], parent.order()[(i + 1) % len]);
+                (x == a && y == b) || (x == b && y == a)
+            })
+        };
+        let child = parent.crossover(&parent);
+        assert!(child.order().windows(2).all(|w| is_edge(w[0], w[1])));
+    }
+
+    #[test]
+    fn test_operators_produce_permutations() {
+        let crossovers = [PermutationCrossover::PartiallyMapped,
+                          PermutationCrossover::Order,
+                          PermutationCrossover::Cycle,
+                          PermutationCrossover::EdgeRecombination];
+        let mutations = [PermutationMutation::Swap,
+                         PermutationMutation::Insert,
+                         PermutationMutation::Inversion,
+                         PermutationMutation::Scramble];
+        for &crossover in &crossovers {
+            for &mutation in &mutations {
+                let config = sorted(15).set_crossover(crossover).set_mutation(mutation);
+                for _ in 0..50 {
+                    let child = config.random().crossover(&config.random()).mutate();
+                    assert!(is_permutation(child.order(), 15));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_crossover_many() {
+        let crossovers = [PermutationCrossover::PartiallyMapped,
+                          PermutationCrossover::Order,
+                          PermutationCrossover::Cycle];
+        for &crossover in &crossovers {
+            let config = sorted(15).set_crossover(crossover);
+            for _ in 0..50 {
+                let parent = config.random();
+                let children = parent.crossover_many(&config.random());
+                assert_eq!(children.len(), parent.num_children());
+                assert!(children.iter().all(|x| is_permutation(x.order(), 15)));
+            }
+        }
+        let config = sorted(15).set_crossover(PermutationCrossover::EdgeRecombination);
+        let parent = config.random();
+        assert_eq!(parent.num_children(), 1);
+        assert_eq!(parent.crossover_many(&config.random()).len(), 1);
+        // Short permutations are copied, as many times as there are children.
+        for &crossover in &[PermutationCrossover::Order, PermutationCrossover::EdgeRecombination] {
+            let parent = sorted(1).set_crossover(crossover).random();
+            assert_eq!(parent.crossover_many(&parent).len(), parent.num_children());
+        }
+    }
+
+    #[test]
+    fn test_crossover_many_advances_rng() {
+        let crossovers = [PermutationCrossover::PartiallyMapped,
+                          PermutationCrossover::Order,
+                          PermutationCrossover::EdgeRecombination];
+        for &crossover in &crossovers {
+            let config = sorted(15).set_crossover(crossover);
+            let (a, b) = (config.random(), config.random());
+            let previous = LocalRng::replace(Some(SimRng::new(0)));
+            a.crossover_many(&b);
+            let after = LocalRng::replace(previous);
+            assert!(after.is_some() && after != Some(SimRng::new(0)));
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let config = sorted(10)
+            .set_crossover(PermutationCrossover::Order)
+            .set_mutation(PermutationMutation::Insert);
+        let mut rng = SimRng::new(0);
+        let mut population: Vec<Permutation<i64>> =
+            (0..50).map(|_| config.random_with(&mut rng)).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(20, 4)))
+            .set_elitism(1)
+            .set_max_iters(300)
+            .set_seed(0)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert!(s.get().unwrap().fitness() >= 8);
+    }
+}
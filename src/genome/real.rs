@@ -0,0 +1,470 @@
+// file: real.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::{self, Rng};
+use sim::LocalRng;
+use rand::distributions::{IndependentSample, Normal};
+use std::fmt;
+use std::sync::Arc;
+use super::FitnessFn;
+
+/// The crossover operator used by a `RealVector`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RealCrossover {
+    /// Whole arithmetic crossover: the child is a random weighted average of both parents.
+    Arithmetic,
+    /// Blend crossover (BLX-alpha): every value of the child is chosen uniformly at random
+    /// from the interval spanned by the parents, extended by `alpha` times its width
+    /// on both sides. A common choice for `alpha` is `0.5`.
+    Blend(f64),
+    /// Simulated binary crossover (SBX) with distribution index `eta`.
+    /// A larger `eta` creates children closer to their parents. A common choice is `15.0`.
+    SimulatedBinary(f64),
+}
+
+/// The mutation operator used by a `RealVector`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RealMutation {
+    /// Add normally distributed noise. The standard deviation is given relative
+    /// to the width of the bounds of every dimension, for example `0.1`.
+    Gaussian(f64),
+    /// Polynomial mutation with distribution index `eta`.
+    /// A larger `eta` results in smaller changes. A common choice is `20.0`.
+    Polynomial(f64),
+}
+
+/// The configuration shared by `RealVector` genomes: their bounds, fitness function
+/// and genetic operators.
+///
+/// ```
+/// use rsgenetic::genome::{RealCrossover, RealMutation, RealVectorConfig};
+/// use rsgenetic::sim::types::F64;
+///
+/// // Minimise the sphere function on [-5, 5]^3.
+/// let config = RealVectorConfig::new(vec![(-5.0, 5.0); 3],
+///                                    |v| F64(-v.values().iter().map(|x| x * x).sum::<f64>()))
+///     .set_crossover(RealCrossover::SimulatedBinary(15.0))
+///     .set_mutation(RealMutation::Polynomial(20.0));
+/// let population: Vec<_> = (0..100).map(|_| config.random()).collect();
+/// ```
+pub struct RealVectorConfig<F: Fitness> {
+    bounds: Vec<(f64, f64)>,
+    crossover: RealCrossover,
+    mutation: RealMutation,
+    mutation_rate: f64,
+    fitness: FitnessFn<RealVector<F>, F>,
+}
+
+impl<F: Fitness> RealVectorConfig<F> {
+    /// Create a configuration for vectors with one dimension for every entry in `bounds`,
+    /// of which the fitness is computed by `fitness`.
+    ///
+    /// Every entry of `bounds` contains the lowest and highest allowed value of its dimension.
+    /// These values should be finite, and the lowest value should not exceed the highest.
+    ///
+    /// By default, blend crossover with `alpha = 0.5` and Gaussian mutation with a relative
+    /// standard deviation of `0.1` are used, and every value is mutated with a probability
+    /// of `1 / n`, with `n` the number of dimensions.
+    pub fn new<G>(bounds: Vec<(f64, f64)>, fitness: G) -> RealVectorConfig<F>
+        where G: Fn(&RealVector<F>) -> F + Send + Sync + 'static
+    {
+        for &(lo, hi) in &bounds {
+            assert!(lo.is_finite() && hi.is_finite() && lo <= hi,
+                    "Invalid bounds: [{}, {}].",
+                    lo,
+                    hi);
+        }
+        RealVectorConfig {
+            mutation_rate: 1.0 / bounds.len().max(1) as f64,
+            bounds,
+            crossover: RealCrossover::Blend(0.5),
+            mutation: RealMutation::Gaussian(0.1),
+            fitness: Arc::new(fitness),
+        }
+    }
+
+    /// Set the crossover operator.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover(mut self, crossover: RealCrossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+    /// Set the mutation operator.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation(mut self, mutation: RealMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+
+    /// Set the probability that a single value is mutated during mutation.
+    /// The rate is clamped to the range `[0, 1]`, and defaults to one over the length.
+    ///
+    /// This rate applies within `mutate`. A simulator first decides whether a child is
+    /// mutated at all with its own mutation rate, which defaults to `1` (see
+    /// `seq::SimulatorBuilder::set_mutation_rate`), so a value of a child is mutated with
+    /// the product of both rates.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Get the bounds of every dimension.
+    pub fn bounds(&self) -> &[(f64, f64)] {
+        &self.bounds
+    }
+
+    /// Create a vector of which every value is chosen uniformly at random within its bounds.
+    pub fn random(&self) -> RealVector<F> {
+        self.random_with(&mut rand::thread_rng())
+    }
+
+    /// Create a vector of which every value is chosen uniformly at random within its bounds,
+    /// using `rng` as the source of randomness.
+    pub fn random_with<R: Rng>(&self, rng: &mut R) -> RealVector<F> {
+        let values = self.bounds
+            .iter()
+            .map(|&(lo, hi)| lo + rng.gen::<f64>() * (hi - lo))
+            .collect();
+        self.from_values(values)
+    }
+
+    /// Create a vector from `values`, which should contain a value for every dimension.
+    /// Values outside of their bounds are clamped.
+    pub fn from_values(&self, mut values: Vec<f64>) -> RealVector<F> {
+        assert_eq!(values.len(),
+                   self.bounds.len(),
+                   "Wrong number of values for this configuration.");
+        for (x, &(lo, hi)) in values.iter_mut().zip(&self.bounds) {
+            *x = x.clamp(lo, hi);
+        }
+        RealVector {
+            values,
+            config: self.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> Clone for RealVectorConfig<F> {
+    fn clone(&self) -> RealVectorConfig<F> {
+        RealVectorConfig {
+            bounds: self.bounds.clone(),
+            crossover: self.crossover,
+            mutation: self.mutation,
+            mutation_rate: self.mutation_rate,
+            fitness: self.fitness.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> fmt::Debug for RealVectorConfig<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RealVectorConfig")
+            .field("bounds", &self.bounds)
+            .field("crossover", &self.crossover)
+            .field("mutation", &self.mutation)
+            .field("mutation_rate", &self.mutation_rate)
+            .finish()
+    }
+}
+
+/// A genome consisting of a fixed number of real values, each within its own bounds.
+///
+/// Create vectors with a `RealVectorConfig`. All operators keep the values
+/// within their bounds. Two vectors are equal if they contain the same values,
+/// regardless of their configuration.
+pub struct RealVector<F: Fitness> {
+    values: Vec<f64>,
+    config: RealVectorConfig<F>,
+}
+
+impl<F: Fitness> RealVector<F> {
+    /// Get the number of dimensions.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether this vector has no dimensions at all.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the values.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Get the configuration of this vector.
+    pub fn config(&self) -> &RealVectorConfig<F> {
+        &self.config
+    }
+
+    /// Create a vector with the same configuration from `values`, clamping them to their bounds.
+    fn with_values(&self, values: Vec<f64>) -> RealVector<F> {
+        self.config.from_values(values)
+    }
+}
+
+/// Create a value from `a` and `b` using blend crossover.
+fn blend<R: Rng>(rng: &mut R, a: f64, b: f64, alpha: f64) -> f64 {
+    let (lo, hi) = (a.min(b), a.max(b));
+    let extent = alpha * (hi - lo);
+    lo - extent + rng.gen::<f64>() * (hi - lo + 2.0 * extent)
+}
+
+/// Create a value from `a` and `b` using simulated binary crossover.
+fn sbx<R: Rng>(rng: &mut R, a: f64, b: f64, eta: f64) -> f64 {
+    let (first, second) = sbx_pair(rng, a, b, eta);
+    if rng.gen::<bool>() { first } else { second }
+}
+
+/// Create two complementary values from `a` and `b` using simulated binary crossover.
+fn sbx_pair<R: Rng>(rng: &mut R, a: f64, b: f64, eta: f64) -> (f64, f64) {
+    let u = rng.gen::<f64>();
+    let beta = if u <= 0.5 {
+        (2.0 * u).powf(1.0 / (eta + 1.0))
+    } else {
+        (1.0 / (2.0 * (1.0 - u))).powf(1.0 / (eta + 1.0))
+    };
+    (0.5 * ((1.0 + beta) * a + (1.0 - beta) * b), 0.5 * ((1.0 - beta) * a + (1.0 + beta) * b))
+}
+
+/// Mutate `x`, which lies within `[lo, hi]`, using polynomial mutation.
+fn polynomial<R: Rng>(rng: &mut R, x: f64, lo: f64, hi: f64, eta: f64) -> f64 {
+    let width = hi - lo;
+    if width <= 0.0 {
+        return x;
+    }
+    let u = rng.gen::<f64>();
+    let power = 1.0 / (eta + 1.0);
+    let delta = if u < 0.5 {
+        let xy = 1.0 - (x - lo) / width;
+        let val = 2.0 * u + (1.0 - 2.0 * u) * xy.powf(eta + 1.0);
+        val.powf(power) - 1.0
+    } else {
+        let xy = 1.0 - (hi - x) / width;
+        let val = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * xy.powf(eta + 1.0);
+        1.0 - val.powf(power)
+    };
+    x + delta * width
+}
+
+impl<F: Fitness> Phenotype<F> for RealVector<F> {
+    fn fitness(&self) -> F {
+        (self.config.fitness)(self)
+    }
+
+    fn crossover(&self, other: &RealVector<F>) -> RealVector<F> {
+        assert_eq!(self.len(), other.len(), "Cannot cross vectors of different lengths.");
+        let mut rng = LocalRng::get();
+        let pairs = self.values.iter().zip(&other.values);
+        let values = match self.config.crossover {
+            RealCrossover::Arithmetic => {
+                let w = rng.gen::<f64>();
+                pairs.map(|(&a, &b)| w * a + (1.0 - w) * b).collect()
+            }
+            RealCrossover::Blend(alpha) => {
+                pairs.map(|(&a, &b)| blend(&mut rng, a, b, alpha)).collect()
+            }
+            RealCrossover::SimulatedBinary(eta) => {
+                pairs.map(|(&a, &b)| sbx(&mut rng, a, b, eta)).collect()
+            }
+        };
+        self.with_values(values)
+    }
+
+    /// Creates two children. Arithmetic and simulated binary crossover create complementary
+    /// children, which lie symmetrically around the parents. Blend crossover samples both
+    /// children independently.
+    fn crossover_many(&self, other: &RealVector<F>) -> Vec<RealVector<F>> {
+        assert_eq!(self.len(), other.len(), "Cannot cross vectors of different lengths.");
+        let mut rng = LocalRng::get();
+        let pairs = self.values.iter().zip(&other.values);
+        let (first, second): (Vec<f64>, Vec<f64>) = match self.config.crossover {
+            RealCrossover::Arithmetic => {
+                let w = rng.gen::<f64>();
+                pairs.map(|(&a, &b)| (w * a + (1.0 - w) * b, (1.0 - w) * a + w * b)).unzip()
+            }
+            RealCrossover::Blend(alpha) => {
+                pairs.map(|(&a, &b)| (blend(&mut rng, a, b, alpha), blend(&mut rng, a, b, alpha)))
+                    .unzip()
+            }
+            RealCrossover::SimulatedBinary(eta) => {
+                pairs.map(|(&a, &b)| sbx_pair(&mut rng, a, b, eta)).unzip()
+            }
+        };
+        vec![self.with_values(first), self.with_values(second)]
+    }
+
+    fn num_children(&self) -> usize {
+        2
+    }
+
+    fn mutate(&self) -> RealVector<F> {
+        let mut rng = LocalRng::get();
+        let rate = self.config.mutation_rate;
+        let values = self.values
+            .iter()
+            .zip(&self.config.bounds)
+            .map(|(&x, &(lo, hi))| {
+                if rng.gen::<f64>() >= rate {
+                    return x;
+                }
+                match self.config.mutation {
+                    RealMutation::Gaussian(sigma) => {
+                        let sd = (sigma * (hi - lo)).abs();
+                        if sd > 0.0 {
+                            x + Normal::new(0.0, sd).ind_sample(&mut rng)
+                        } else {
+                            x
+                        }
+                    }
+                    RealMutation::Polynomial(eta) => polynomial(&mut rng, x, lo, hi, eta),
+                }
+            })
+            .collect();
+        self.with_values(values)
+    }
+}
+
+impl<F: Fitness> Clone for RealVector<F> {
+    fn clone(&self) -> RealVector<F> {
+        RealVector {
+            values: self.values.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<F: Fitness> PartialEq for RealVector<F> {
+    fn eq(&self, other: &RealVector<F>) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<F: Fitness> fmt::Debug for RealVector<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RealVector").field("values", &self.values).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use genome::*;
+    use pheno::Phenotype;
+    use sim::*;
+    use sim::select::TournamentSelector;
+    use sim::types::F64;
+
+    fn sphere(bounds: Vec<(f64, f64)>) -> RealVectorConfig<F64> {
+        RealVectorConfig::new(bounds,
+                              |v| F64(-v.values().iter().map(|x| x * x).sum::<f64>()))
+    }
+
+    fn within_bounds(v: &RealVector<F64>) -> bool {
+        v.values().iter().zip(v.config().bounds()).all(|(&x, &(lo, hi))| x >= lo && x <= hi)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_bounds() {
+        sphere(vec![(1.0, -1.0)]);
+    }
+
+    #[test]
+    fn test_from_values_clamps() {
+        let v = sphere(vec![(0.0, 1.0), (-2.0, 2.0)]).from_values(vec![3.0, -5.0]);
+        assert_eq!(v.values(), &[1.0, -2.0]);
+    }
+
+    #[test]
+    fn test_operators_respect_bounds() {
+        let crossovers = [RealCrossover::Arithmetic,
+                          RealCrossover::Blend(0.5),
+                          RealCrossover::SimulatedBinary(2.0)];
+        let mutations = [RealMutation::Gaussian(0.5), RealMutation::Polynomial(5.0)];
+        for &crossover in &crossovers {
+            for &mutation in &mutations {
+                let config = sphere(vec![(-1.0, 1.0), (0.0, 10.0), (3.0, 3.0)])
+                    .set_crossover(crossover)
+                    .set_mutation(mutation)
+                    .set_mutation_rate(1.0);
+                for _ in 0..100 {
+                    let a = config.random();
+                    let b = config.random();
+                    assert!(within_bounds(&a));
+                    let child = a.crossover(&b).mutate();
+                    assert!(within_bounds(&child));
+                    assert_eq!(child.values()[2], 3.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_between_parents() {
+        let config = sphere(vec![(-10.0, 10.0); 2]).set_crossover(RealCrossover::Arithmetic);
+        let a = config.from_values(vec![-1.0, 1.0]);
+        let b = config.from_values(vec![1.0, 3.0]);
+        for _ in 0..20 {
+            let child = a.crossover(&b);
+            // Both values move by the same fraction from `b` towards `a`.
+            assert!((child.values()[1] - child.values()[0] - 2.0).abs() < 1e-9);
+            assert!(child.values()[0] >= -1.0 && child.values()[0] <= 1.0);
+        }
+    }
+
+    #[test]
+    fn test_crossover_many_complementary() {
+        for &crossover in &[RealCrossover::Arithmetic, RealCrossover::SimulatedBinary(15.0)] {
+            let config = sphere(vec![(-100.0, 100.0); 2]).set_crossover(crossover);
+            let a = config.from_values(vec![-1.0, 1.0]);
+            let b = config.from_values(vec![1.0, 3.0]);
+            for _ in 0..20 {
+                let children = a.crossover_many(&b);
+                assert_eq!(children.len(), 2);
+                // The children lie symmetrically around the parents.
+                for i in 0..2 {
+                    let sum = children[0].values()[i] + children[1].values()[i];
+                    assert!((sum - a.values()[i] - b.values()[i]).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sphere() {
+        let config = sphere(vec![(-5.0, 5.0); 3])
+            .set_crossover(RealCrossover::SimulatedBinary(15.0))
+            .set_mutation(RealMutation::Polynomial(20.0));
+        let mut rng = SimRng::new(0);
+        let mut population: Vec<RealVector<F64>> =
+            (0..50).map(|_| config.random_with(&mut rng)).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(20, 4)))
+            .set_elitism(1)
+            .set_max_iters(300)
+            .set_seed(0)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert!(s.get().unwrap().fitness().value() > -0.1);
+    }
+}
@@ -35,16 +35,35 @@
 //! # Features
 //! ## Available Simulators
 //!
-//! There is currently only one, sequential, simulator. This simulator will run
-//! the genetic algorithm on a single thread.
+//! There are currently four simulators:
+//!
+//! * `seq::Simulator` runs the genetic algorithm on a single thread.
+//! * `par::Simulator` runs the genetic algorithm like `seq::Simulator`, but evaluates
+//!   fitness values and performs crossover and mutation on multiple threads.
+//!   Use this simulator if your fitness function is expensive.
+//!   The number of threads can be set with `set_threads` on its `SimulatorBuilder`.
+//! * `nsga2::Simulator` optimises several objectives at the same time using NSGA-II.
+//!   Its fitness type must implement `MultiFitness`, for example `types::Objectives`.
+//!   Use `pareto_front()` to get all non-dominated individuals.
+//! * `island::Simulator` splits the population into islands that evolve independently,
+//!   and periodically migrates individuals between them along a `Topology`.
+//!   Use this simulator if a single population converges too early.
+//!
+//! Simulators borrow the population that is passed to `builder`, and move the final population
+//! back into it when they are dropped. `seq::Simulator` and `par::Simulator` can also own their
+//! population: create them with `owned_builder` and get the final population with
+//! `into_population`. Such a simulator can be stored in a struct or moved to another thread.
 //!
 //! ## Available Selection Types
 //!
-//! There are currently four selection types available:
+//! There are currently six selection types available:
 //!
 //! * Maximize
 //! * Tournament
 //! * Stochastic
+//! * Roulette
+//! * Stochastic Universal Sampling
+//! * Rank
 //!
 //! There is a short explanation for each of these below. For more information, look at the
 //! documentation of individual selectors.
@@ -63,7 +82,54 @@
 //!
 //! ### Stochastic
 //!
-//! Stochastic takes 1 parameter: the count. The resulting number of parents is `count`.
+//! Stochastic takes 1 parameter: the count. Selection happens at equidistant positions in the
+//! population, without looking at fitness. The resulting number of parents is `count`.
+//!
+//! ### Roulette
+//!
+//! Roulette takes 2 parameters: the count and a function that converts a fitness value to
+//! a non-negative weight. Every parent is selected with a probability proportional to its
+//! weight. A windowed variant subtracts the lowest weight first, which makes it suitable
+//! for negative fitness values. The resulting number of parents is `count`.
+//!
+//! ### Stochastic Universal Sampling
+//!
+//! Stochastic Universal Sampling takes the same parameters as Roulette, but places `count`
+//! equally spaced pointers over the cumulative weights, so that the number of times an
+//! individual is selected is always close to its expected value.
+//! The resulting number of parents is `count`.
+//!
+//! ### Rank
+//!
+//! Rank takes 2 parameters: the count and either a selection pressure (linear ranking) or
+//! a base (exponential ranking). Individuals are selected with a probability that depends only
+//! on their rank in the population, so the scale of the fitness values does not matter.
+//! The resulting number of parents is `count`.
+//!
+//! ## Built-in Genomes
+//!
+//! The `genome` module contains ready-made `Phenotype` implementations with standard
+//! crossover and mutation operators:
+//!
+//! * `BitString`: a fixed number of bits, with one-point, two-point or uniform crossover and
+//!   bit-flip mutation.
+//! * `RealVector`: a fixed number of real values within bounds, with arithmetic, blend (BLX-alpha)
+//!   or simulated binary crossover and Gaussian or polynomial mutation.
+//! * `Permutation`: an ordering of the elements `0..n`, with partially mapped, order, cycle or
+//!   edge recombination crossover and swap, insert, inversion or scramble mutation.
+//! * `gp::Tree`: an expression tree for genetic programming, built from a user-defined
+//!   `gp::PrimitiveSet`, with subtree crossover and subtree, point or hoist mutation.
+//!
+//! You only need to provide a fitness function to use them.
+//!
+//! ## Initialisation
+//!
+//! Instead of creating the initial population yourself, you can pass an `Initializer` to
+//! `set_initializer` on the `SimulatorBuilder`. The `init` module contains initializers
+//! that call a generator function, sample real vectors from a Latin hypercube, start from
+//! known solutions, use opposition-based initialisation or remove duplicates. The
+//! configurations of the built-in genomes create individuals uniformly at random.
+//! Only `seq::Simulator` and `par::Simulator` have `set_initializer`.
 //!
 //! ## Early Stopping
 //!
@@ -71,19 +137,111 @@
 //! doesn't improve by a large amount for a number of iterations. This can be done by calling the
 //! `set_early_stop(delta: Fitness, n_iters: u32)` function on the `SimulatorBuilder`.
 //!
+//! ## Stopping Criteria
+//!
+//! For more control over when a simulation stops, pass a `StopCriterion` to
+//! `set_stop_criterion` on the `SimulatorBuilder`. The `stop` module contains criteria for
+//! a maximum number of iterations, stagnation, a time limit, a target fitness, a budget of
+//! fitness evaluations and a collapse of diversity, which can be combined with `any`,
+//! `all` and `not`. A criterion replaces the default maximum number of iterations.
+//! Stopping criteria are available for `seq::Simulator` and `par::Simulator`.
+//!
+//! ## Termination
+//!
+//! Call `stop_reason()` on a `Simulation` after it has finished to find out why it stopped:
+//! because it reached the maximum number of iterations, because of early stopping, because
+//! its stop criterion was met, because an observer requested it or because of an error.
+//!
+//! ## Errors
+//!
+//! If a simulation fails, `get()` returns a `SimError`, which implements `std::error::Error`.
+//! Match on its variants to find out what went wrong, for example an invalid parameter of a
+//! selector or a population that is too small.
+//!
+//! ## Replacement
+//!
+//! After the children of a step have been created, a replacement strategy chooses which
+//! individuals survive. By default, individuals are killed off at evenly spaced random positions
+//! to make room for the children. Call `set_replacement` on the `SimulatorBuilder` to use
+//! another strategy from the `replace` module: generational, replace-worst, (mu+lambda),
+//! (mu,lambda) or random replacement. You can also implement the `Replacement` trait yourself.
+//!
+//! ## Elitism
+//!
+//! Most replacement strategies may kill off the best individual. Call `set_elitism(n: usize)`
+//! on the `SimulatorBuilder` to guarantee that the `n` best individuals survive every step.
+//!
+//! ## Crossover and Mutation Rates
+//!
+//! By default, every child is created by crossover and then mutated. Call
+//! `set_crossover_rate(pc: f64)` and `set_mutation_rate(pm: f64)` on the `SimulatorBuilder`
+//! to apply crossover and mutation with the probabilities `pc` and `pm` instead. Without
+//! crossover, the parents are cloned instead, so every pair creates the same number of
+//! children either way.
+//!
+//! Crossover operators that naturally create several children, such as one-point crossover,
+//! can implement `Phenotype::crossover_many` and `Phenotype::num_children`. The simulators
+//! add every child to the offspring, and the built-in genomes create two children where their
+//! operator allows.
+//!
+//! ## Observers
+//!
+//! To watch the progress of a simulation, implement the `observer::Observer` trait and register
+//! it with `add_observer` on the `SimulatorBuilder`. Observers are notified at the start and end
+//! of every generation, after selection, when a new best individual is found and when the
+//! simulation terminates. They can also abort the simulation. Only `seq::Simulator` and
+//! `par::Simulator` support observers.
+//!
+//! ## Statistics
+//!
+//! Simulators record statistics about the population at the end of every generation: the best,
+//! worst, median and mean fitness, the population size, an estimate of the diversity and the
+//! time spent. Use `history()` on a `Simulation` to get them.
+//!
+//! ## Fitness Caching
+//!
+//! The fitness of every individual is evaluated exactly once, when it enters the population.
+//! Simulators and selectors work on `Evaluated` individuals, which carry their cached fitness
+//! value. This is why `Fitness` types need to implement `Clone`.
+//!
+//! ## Checkpoints
+//!
+//! Call `checkpoint()` on a `seq::Simulator` or `par::Simulator` to capture the state of a
+//! simulation: the population, the iteration counter, the early stopping state, the random
+//! number generator and the statistics. Pass the `Checkpoint` to `resume` on a new
+//! `SimulatorBuilder` to continue where the simulation left off. Settings that are not part
+//! of the state, such as the selector, must be set again.
+//!
+//! Enable the `serde` feature to serialise a `Checkpoint` with any `serde` format, so that
+//! long runs can survive a restart:
+//!
+//! ```ignore
+//! [dependencies]
+//! rsgenetic = { version = "^1.7.0", features = ["serde"] }
+//! ```
+//!
+//! ## Reproducibility
+//!
+//! Simulators and selectors draw all of their randomness from a `SimRng`.
+//! Call `set_seed(seed: u64)` or `set_rng(rng: SimRng)` on the `SimulatorBuilder` to make
+//! runs reproducible: the same seed, population and settings always yield the same result,
+//! provided that your `Phenotype` implementation does not use randomness of its own.
+//! A simulation resumed from a `Checkpoint` behaves exactly like the original one would have.
+//!
 //! # Examples
 //!
 //! ## Implementing the `Fitness` trait
 //!
 //! Note that, if your fitness type is an integer type, you
-//! do not need to write a wrapper struct around this integer. See
+//! do not need to write a wrapper struct around this integer. For floating
+//! point fitness values, you can use the `F32` and `F64` wrappers. See
 //! the `types` module documentation for more details.
 //!
 //! ```
 //! use rsgenetic::pheno::*;
 //! use std::cmp::Ordering;
 //!
-//! #[derive(Eq, PartialEq, PartialOrd, Ord)]
+//! #[derive(Clone, Eq, PartialEq, PartialOrd, Ord)]
 //! struct MyFitness {
 //!     value: i32,
 //! }
@@ -181,10 +339,18 @@
         unused_import_braces, unused_qualifications)]
 
 extern crate rand;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 /// Contains the definition of a Phenotype.
 pub mod pheno;
 /// Contains implementations of Simulators, which can run genetic algorithms.
 pub mod sim;
+/// Contains ready-made genomes, which implement Phenotype.
+pub mod genome;
 /// Contains code used by unit tests.
+#[cfg(test)]
 mod test;
@@ -20,16 +20,47 @@
 /// **Make sure the following statement holds:**
 /// A `Phenotype` with a `Fitness` value of `f1` performs better than
 /// another `Phenotype` with a `Fitness` value of `f2` iff `f1 > f2`.
-pub trait Fitness: Ord + Eq {
+pub trait Fitness: Ord + Eq + Clone {
     /// Get the zero value of this `Fitness` value.
     /// The internal value should be 0.
     fn zero() -> Self;
     /// Get the absolute difference between two `Fitness` values.
     fn abs_diff(&self, other: &Self) -> Self;
+    /// Returns whether this `Fitness` value is valid.
+    ///
+    /// A simulation step fails if any individual in the population has an invalid
+    /// fitness value. By default, every value is valid.
+    fn is_valid(&self) -> bool {
+        true
+    }
+    /// Convert this `Fitness` value to a number, if it has a meaningful numeric value.
+    ///
+    /// This is used for statistics, such as the mean fitness of a population.
+    /// By default, `None` is returned.
+    fn to_f64(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// A `MultiFitness` value consists of several objectives, which are optimised
+/// at the same time by a multi-objective simulator such as `::sim::nsga2::Simulator`.
+///
+/// **Every objective is maximised**: a higher objective value is better.
+/// All values of one problem should have the same number of objectives.
+///
+/// The ordering required by `Fitness` is only used where a single best individual
+/// is needed, for example by `Simulation::get`. Selection and survival are based on
+/// Pareto dominance instead.
+pub trait MultiFitness: Fitness {
+    /// Get the number of objectives.
+    fn num_objectives(&self) -> usize;
+    /// Get the value of the objective with index `index`.
+    fn objective(&self, index: usize) -> f64;
 }
 
 /// Defines what a Phenotype is.
-/// A Phenotype can breed with other Phenotypes, resulting in a single child.
+/// A Phenotype can breed with other Phenotypes, resulting in a single child,
+/// or in several children if `crossover_many` is implemented.
 /// A Phenotype can also be mutated.
 /// Finally, a Phenotype has a certain fitness value associated with it.
 ///
@@ -40,7 +71,26 @@ pub trait Phenotype<F>: Clone
     /// Calculate the fitness of this Phenotype.
     fn fitness(&self) -> F;
     /// Perform crossover on this Phenotype, returning a new Phenotype.
-    fn crossover(&self, &Self) -> Self;
+    fn crossover(&self, other: &Self) -> Self;
+    /// Perform crossover on this Phenotype, returning one or more new Phenotypes.
+    ///
+    /// Implement this for crossover operators that naturally create several children,
+    /// such as one-point crossover, which creates two complementary children.
+    /// The simulators use this function instead of `crossover`, and add every child to
+    /// the offspring. By default, the single child of `crossover` is returned.
+    ///
+    /// The result should contain exactly `num_children()` children.
+    fn crossover_many(&self, other: &Self) -> Vec<Self> {
+        vec![self.crossover(other)]
+    }
+    /// Get the number of children that `crossover_many` creates, which should be at least one.
+    ///
+    /// When a simulator skips crossover for a pair of parents, it creates this many clones
+    /// of the parents instead, so the number of children per pair does not depend on
+    /// whether crossover happened. By default, `1` is returned.
+    fn num_children(&self) -> usize {
+        1
+    }
     /// Perform mutation on this Phenotype, returning a new Phenotype.
     fn mutate(&self) -> Self;
 }
@@ -0,0 +1,105 @@
+// file: checkpoint.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Checkpoints capture the state of a simulation, so that it can be resumed later.
+//!
+//! Call `checkpoint()` on a `seq::Simulator` or a `par::Simulator` to create a `Checkpoint`,
+//! and pass it to `resume` on a new `SimulatorBuilder` to continue the simulation.
+//! The resumed simulation behaves exactly like the original one would have, provided that
+//! the `Phenotype` implementation is deterministic and the same settings are used.
+//!
+//! With the `serde` feature enabled, a `Checkpoint` implements `Serialize` and `Deserialize`
+//! if the phenotype and fitness types do, so it can be written to disk.
+
+use pheno::{Fitness, Phenotype};
+use super::{Evaluated, NanoSecond, SimRng};
+use super::earlystopper::EarlyStopper;
+use super::stats::Stats;
+
+/// The state of a simulation at the end of a step.
+///
+/// A checkpoint contains the population with its fitness values, the iteration counter,
+/// the state of early stopping, the state of the random number generator and the
+/// statistics recorded so far. It does not contain the settings of the simulation,
+/// such as the selector or the maximum number of iterations.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Checkpoint<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    pub(crate) population: Vec<Evaluated<T, F>>,
+    pub(crate) iterations: u64,
+    pub(crate) evaluations: u64,
+    pub(crate) earlystopper: Option<EarlyStopper<F>>,
+    /// The highest fitness reported to the observers.
+    pub(crate) best: Option<F>,
+    pub(crate) history: Vec<Stats<F>>,
+    pub(crate) rng: SimRng,
+    pub(crate) duration: Option<NanoSecond>,
+}
+
+impl<T, F> Checkpoint<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Get the population at the time of the checkpoint.
+    pub fn population(&self) -> &[Evaluated<T, F>] {
+        &self.population
+    }
+
+    /// Get the number of iterations that had been run at the time of the checkpoint.
+    pub fn iterations(&self) -> u64 {
+        self.iterations
+    }
+
+    /// Get the statistics that had been recorded at the time of the checkpoint.
+    pub fn history(&self) -> &[Stats<F>] {
+        &self.history
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use ::sim::*;
+    use ::sim::select::*;
+    use serde_json;
+    use test::{MyFitness, Test};
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut population: Vec<Test> = (0..50).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(10, 4)))
+            .set_early_stop(MyFitness { f: 1 }, 20)
+            .set_max_iters(5)
+            .set_seed(11)
+            .build();
+        s.run();
+        let checkpoint = s.checkpoint();
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let restored: checkpoint::Checkpoint<Test, MyFitness> = serde_json::from_str(&json)
+            .unwrap();
+        assert_eq!(restored.iterations(), 5);
+        assert!(restored.history() == checkpoint.history());
+        assert_eq!(restored.rng, checkpoint.rng);
+        let fitnesses = |c: &checkpoint::Checkpoint<Test, MyFitness>| -> Vec<i64> {
+            c.population().iter().map(|x| x.fitness().f).collect()
+        };
+        assert_eq!(fitnesses(&restored), fitnesses(&checkpoint));
+    }
+}
@@ -19,6 +19,7 @@ use pheno::Fitness;
 
 /// Used for early stopping.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EarlyStopper<F: Fitness> {
     /// Minimum difference required for early stopping.
     delta: F,
@@ -66,6 +67,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_early_stopper_reset() {
         let mut stopper = EarlyStopper::new(MyFitness::new(10), 5);
         for _ in 0..4 {
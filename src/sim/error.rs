@@ -0,0 +1,171 @@
+// file: error.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::Fitness;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// The errors that can occur while running a `Simulation`.
+///
+/// Custom selectors and replacement strategies can use `Other` to report errors
+/// of their own.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimError {
+    /// A parameter, for example of a selector, has an invalid value.
+    InvalidParameter {
+        /// The name of the parameter.
+        name: &'static str,
+        /// The value of the parameter.
+        value: String,
+        /// A description of the values that are allowed.
+        expected: &'static str,
+    },
+    /// A weight computed by a fitness proportionate selector is invalid.
+    InvalidWeight {
+        /// The weight, or the sum of all weights.
+        weight: f64,
+        /// A description of the weights that are allowed.
+        expected: &'static str,
+    },
+    /// The topology of an island model is invalid. Contains a description of the problem.
+    InvalidTopology(String),
+    /// The population contains too few individuals.
+    PopulationTooSmall {
+        /// The number of individuals in the population.
+        size: usize,
+        /// The minimum number of individuals that is needed.
+        minimum: usize,
+    },
+    /// An individual in the population has an invalid fitness value,
+    /// according to `Fitness::is_valid`.
+    InvalidFitness {
+        /// The index of the individual in the population. The islands of an island
+        /// model are counted one after the other.
+        index: usize,
+        /// The fitness value as a number, if `Fitness::to_f64` gives one.
+        value: Option<f64>,
+    },
+    /// A replacement strategy received more children than there are individuals
+    /// that can be replaced.
+    TooManyChildren {
+        /// The number of children.
+        children: usize,
+        /// The number of individuals that can be replaced.
+        available: usize,
+    },
+    /// A replacement strategy received fewer children than it needs.
+    NotEnoughChildren {
+        /// The number of children.
+        children: usize,
+        /// The number of children that is needed.
+        required: usize,
+    },
+    /// Any other error. Contains a message indicating the error.
+    Other(String),
+}
+
+/// The error returned by `Simulation::get` when the population is empty.
+pub(crate) static EMPTY_POPULATION: SimError = SimError::PopulationTooSmall {
+    size: 0,
+    minimum: 1,
+};
+
+impl SimError {
+    /// Create an `InvalidParameter` error for the parameter `name`, which has
+    /// the invalid `value`.
+    pub fn invalid_parameter<V: Display>(name: &'static str,
+                                         value: V,
+                                         expected: &'static str)
+                                         -> SimError {
+        SimError::InvalidParameter {
+            name,
+            value: value.to_string(),
+            expected,
+        }
+    }
+
+    /// Create an `InvalidFitness` error for the individual at `index`,
+    /// which has the invalid `fitness`.
+    pub fn invalid_fitness<F: Fitness>(index: usize, fitness: &F) -> SimError {
+        SimError::InvalidFitness {
+            index,
+            value: fitness.to_f64(),
+        }
+    }
+}
+
+impl Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SimError::InvalidParameter { name, ref value, expected } => {
+                write!(f, "Invalid parameter `{}`: {}. {}", name, value, expected)
+            }
+            SimError::InvalidWeight { weight, expected } => {
+                write!(f, "Invalid weight: {}. {}", weight, expected)
+            }
+            SimError::InvalidTopology(ref reason) => write!(f, "Invalid topology: {}", reason),
+            SimError::PopulationTooSmall { size, minimum } => {
+                write!(f,
+                       "The population contains {} individuals, but at least {} are needed.",
+                       size,
+                       minimum)
+            }
+            SimError::InvalidFitness { index, value: Some(value) } => {
+                write!(f, "Individual {} has an invalid fitness value: {}.", index, value)
+            }
+            SimError::InvalidFitness { index, value: None } => {
+                write!(f, "Individual {} has an invalid fitness value.", index)
+            }
+            SimError::TooManyChildren { children, available } => {
+                write!(f,
+                       "Too many children: {}. There are only {} individuals that can be \
+                        replaced. Use less parents, or a smaller elite.",
+                       children,
+                       available)
+            }
+            SimError::NotEnoughChildren { children, required } => {
+                write!(f,
+                       "Not enough children: {}. At least {} children are needed to replace \
+                        the population. Use more parents, or a larger elite.",
+                       children,
+                       required)
+            }
+            SimError::Other(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for SimError {}
+
+#[cfg(test)]
+mod tests {
+    use super::SimError;
+
+    #[test]
+    fn test_display() {
+        let error = SimError::invalid_parameter("count", 3, "Should be a multiple of two.");
+        assert_eq!(error.to_string(),
+                   "Invalid parameter `count`: 3. Should be a multiple of two.");
+        let error = SimError::PopulationTooSmall { size: 1, minimum: 2 };
+        assert_eq!(error.to_string(),
+                   "The population contains 1 individuals, but at least 2 are needed.");
+        let error = SimError::InvalidFitness {
+            index: 4,
+            value: Some(f64::NAN),
+        };
+        assert_eq!(error.to_string(), "Individual 4 has an invalid fitness value: NaN.");
+    }
+}
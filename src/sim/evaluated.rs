@@ -0,0 +1,82 @@
+// file: evaluated.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+
+/// A `Phenotype` together with its cached fitness value.
+///
+/// Simulators evaluate the fitness of every individual exactly once, when it
+/// enters the population, and store the result in an `Evaluated`.
+/// Selectors compare individuals using this cached value.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Evaluated<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    pheno: T,
+    fitness: F,
+}
+
+impl<T, F> Evaluated<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Evaluate the fitness of `pheno` and store it alongside `pheno`.
+    pub fn new(pheno: T) -> Evaluated<T, F> {
+        let fitness = pheno.fitness();
+        Evaluated { pheno, fitness }
+    }
+
+    /// Create an `Evaluated` from a phenotype and its already computed fitness.
+    pub(crate) fn with_fitness(pheno: T, fitness: F) -> Evaluated<T, F> {
+        Evaluated { pheno, fitness }
+    }
+
+    /// Get the phenotype.
+    pub fn pheno(&self) -> &T {
+        &self.pheno
+    }
+
+    /// Get the cached fitness of the phenotype.
+    pub fn fitness(&self) -> &F {
+        &self.fitness
+    }
+
+    /// Unwrap the phenotype, discarding its fitness.
+    pub fn into_pheno(self) -> T {
+        self.pheno
+    }
+}
+
+/// Evaluate every individual of `population`, in order.
+pub(crate) fn evaluate_all<T, F>(population: &[T]) -> Vec<F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    population.iter().map(Phenotype::fitness).collect()
+}
+
+/// Pair every individual of `population` with its fitness value from `fitnesses`.
+pub(crate) fn zip_evaluated<T, F>(population: Vec<T>, fitnesses: Vec<F>) -> Vec<Evaluated<T, F>>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    population.into_iter()
+        .zip(fitnesses)
+        .map(|(pheno, fitness)| Evaluated::with_fitness(pheno, fitness))
+        .collect()
+}
@@ -0,0 +1,394 @@
+// file: init.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The init module provides a trait that can be implemented to create the initial
+//! population of a `Simulation`, together with a couple of useful strategies.
+//!
+//! An initializer is set with `set_initializer` on the `SimulatorBuilder`. The individuals
+//! it creates are added to the population passed to `builder`, so known solutions can
+//! either be passed to `builder` directly or be wrapped in a `Seeded` initializer.
+//! `seq::Simulator` and `par::Simulator` accept initializers; for the island and NSGA-II
+//! simulators, call `Initializer::initialize` yourself and pass the result to `builder`.
+//!
+//! The configurations of the built-in genomes are initializers themselves: they create
+//! individuals uniformly at random.
+//!
+//! ```
+//! use rsgenetic::genome::RealVectorConfig;
+//! use rsgenetic::sim::init::*;
+//! use rsgenetic::sim::types::F64;
+//!
+//! let config = RealVectorConfig::new(vec![(-5.0, 5.0); 3],
+//!                                    |v| F64(-v.values().iter().map(|x| x * x).sum::<f64>()));
+//! // Spread the population evenly over the search space, and drop duplicates.
+//! let initializer = Deduplicated::new(LatinHypercube::new(config));
+//! ```
+
+use genome::{BitString, BitStringConfig, Permutation, PermutationConfig, RealVector,
+             RealVectorConfig};
+use genome::gp::{Tree, TreeConfig};
+use pheno::{Fitness, Phenotype};
+use rand::Rng;
+use sim::SimRng;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+/// Creates the individuals of an initial population.
+pub trait Initializer<T>: Debug {
+    /// Create `size` individuals, using `rng` as the source of randomness.
+    ///
+    /// Implementations may return fewer individuals if they are unable to create more,
+    /// but never more than `size`.
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<T>;
+}
+
+impl<F: Fitness> Initializer<BitString<F>> for BitStringConfig<F> {
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<BitString<F>> {
+        (0..size).map(|_| self.random_with(rng)).collect()
+    }
+}
+
+impl<F: Fitness> Initializer<RealVector<F>> for RealVectorConfig<F> {
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<RealVector<F>> {
+        (0..size).map(|_| self.random_with(rng)).collect()
+    }
+}
+
+impl<F: Fitness> Initializer<Permutation<F>> for PermutationConfig<F> {
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<Permutation<F>> {
+        (0..size).map(|_| self.random_with(rng)).collect()
+    }
+}
+
+/// Trees are created using ramped half-and-half initialisation.
+impl<V, F> Initializer<Tree<V, F>> for TreeConfig<V, F>
+    where V: Clone + Debug + Send + Sync + 'static,
+          F: Fitness
+{
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<Tree<V, F>> {
+        self.ramped_half_and_half_with(rng, size)
+    }
+}
+
+/// Creates every individual by calling a generator function.
+pub struct FromFn<G> {
+    generator: G,
+}
+
+impl<G> FromFn<G> {
+    /// Create an initializer that calls `generator` for every individual.
+    pub fn new<T>(generator: G) -> FromFn<G>
+        where G: FnMut(&mut SimRng) -> T
+    {
+        FromFn { generator }
+    }
+}
+
+impl<T, G> Initializer<T> for FromFn<G>
+    where G: FnMut(&mut SimRng) -> T
+{
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<T> {
+        (0..size).map(|_| (self.generator)(rng)).collect()
+    }
+}
+
+impl<G> Debug for FromFn<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FromFn").finish_non_exhaustive()
+    }
+}
+
+/// Creates real vectors using Latin hypercube sampling.
+///
+/// The range of every dimension is divided into as many strata of equal width as
+/// there are individuals to create, and every stratum contains exactly one value
+/// of that dimension. This spreads the population more evenly over the search space
+/// than uniform sampling does.
+#[derive(Clone)]
+pub struct LatinHypercube<F: Fitness> {
+    config: RealVectorConfig<F>,
+}
+
+impl<F: Fitness> LatinHypercube<F> {
+    /// Create an initializer that samples vectors of `config`.
+    pub fn new(config: RealVectorConfig<F>) -> LatinHypercube<F> {
+        LatinHypercube { config }
+    }
+}
+
+impl<F: Fitness> Initializer<RealVector<F>> for LatinHypercube<F> {
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<RealVector<F>> {
+        let mut values = vec![Vec::with_capacity(self.config.bounds().len()); size];
+        for &(lo, hi) in self.config.bounds() {
+            let mut strata: Vec<usize> = (0..size).collect();
+            rng.shuffle(&mut strata);
+            for (individual, stratum) in values.iter_mut().zip(strata) {
+                let offset = (stratum as f64 + rng.gen::<f64>()) / size as f64;
+                individual.push(lo + offset * (hi - lo));
+            }
+        }
+        values.into_iter().map(|v| self.config.from_values(v)).collect()
+    }
+}
+
+impl<F: Fitness> Debug for LatinHypercube<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LatinHypercube")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Starts the population with known solutions, and creates the remaining
+/// individuals with another initializer.
+///
+/// If there are more solutions than individuals to create, only the first
+/// ones are used.
+#[derive(Clone)]
+pub struct Seeded<T, I> {
+    solutions: Vec<T>,
+    initializer: I,
+}
+
+impl<T, I> Seeded<T, I>
+    where T: Clone,
+          I: Initializer<T>
+{
+    /// Create an initializer that returns `solutions`, followed by individuals
+    /// created by `initializer`.
+    pub fn new(solutions: Vec<T>, initializer: I) -> Seeded<T, I> {
+        Seeded {
+            solutions,
+            initializer,
+        }
+    }
+}
+
+impl<T, I> Initializer<T> for Seeded<T, I>
+    where T: Clone,
+          I: Initializer<T>
+{
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<T> {
+        let mut result: Vec<T> = self.solutions.iter().take(size).cloned().collect();
+        let remaining = size - result.len();
+        result.append(&mut self.initializer.initialize(remaining, rng));
+        result
+    }
+}
+
+impl<T, I: Debug> Debug for Seeded<T, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Seeded")
+            .field("solutions", &self.solutions.len())
+            .field("initializer", &self.initializer)
+            .finish()
+    }
+}
+
+/// A genome for which an opposite point in the search space is defined.
+pub trait Opposite {
+    /// Get the opposite of `self`.
+    fn opposite(&self) -> Self;
+}
+
+/// The opposite of a real vector mirrors every value within its bounds:
+/// the opposite of `x` in `[lo, hi]` is `lo + hi - x`.
+impl<F: Fitness> Opposite for RealVector<F> {
+    fn opposite(&self) -> RealVector<F> {
+        let values = self.values()
+            .iter()
+            .zip(self.config().bounds())
+            .map(|(&x, &(lo, hi))| lo + hi - x)
+            .collect();
+        self.config().from_values(values)
+    }
+}
+
+/// The opposite of a bit string has every bit flipped.
+impl<F: Fitness> Opposite for BitString<F> {
+    fn opposite(&self) -> BitString<F> {
+        let mut result = self.clone();
+        for i in 0..result.len() {
+            result.flip(i);
+        }
+        result
+    }
+}
+
+/// Opposition-based initialisation: creates individuals with another initializer,
+/// adds the opposite of each of them, and keeps the fittest half.
+///
+/// This evaluates the fitness of twice as many individuals as are returned.
+/// These evaluations are not counted by the `Simulation`.
+pub struct OppositionBased<I, F> {
+    initializer: I,
+    fitness: PhantomData<fn() -> F>,
+}
+
+impl<I, F: Fitness> OppositionBased<I, F> {
+    /// Create an initializer that takes its candidates from `initializer`.
+    pub fn new(initializer: I) -> OppositionBased<I, F> {
+        OppositionBased {
+            initializer,
+            fitness: PhantomData,
+        }
+    }
+}
+
+impl<T, I, F> Initializer<T> for OppositionBased<I, F>
+    where T: Phenotype<F> + Opposite,
+          I: Initializer<T>,
+          F: Fitness
+{
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<T> {
+        let candidates = self.initializer.initialize(size, rng);
+        let opposites: Vec<T> = candidates.iter().map(Opposite::opposite).collect();
+        let mut evaluated: Vec<(F, T)> = candidates.into_iter()
+            .chain(opposites)
+            .map(|x| (x.fitness(), x))
+            .collect();
+        evaluated.sort_by(|a, b| b.0.cmp(&a.0));
+        evaluated.truncate(size);
+        evaluated.into_iter().map(|(_, x)| x).collect()
+    }
+}
+
+impl<I: Debug, F> Debug for OppositionBased<I, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OppositionBased")
+            .field("initializer", &self.initializer)
+            .finish()
+    }
+}
+
+/// Removes duplicate individuals created by another initializer.
+///
+/// Duplicates are replaced by asking the inner initializer for more individuals,
+/// at most `MAX_ROUNDS` times. If the search space is too small to contain enough
+/// distinct individuals, fewer individuals than requested are returned.
+#[derive(Clone, Debug)]
+pub struct Deduplicated<I> {
+    initializer: I,
+}
+
+impl<I> Deduplicated<I> {
+    /// The maximum number of times the inner initializer is asked for more individuals.
+    pub const MAX_ROUNDS: usize = 100;
+
+    /// Create an initializer that removes duplicates created by `initializer`.
+    pub fn new(initializer: I) -> Deduplicated<I> {
+        Deduplicated { initializer }
+    }
+}
+
+impl<T, I> Initializer<T> for Deduplicated<I>
+    where T: PartialEq,
+          I: Initializer<T>
+{
+    fn initialize(&mut self, size: usize, rng: &mut SimRng) -> Vec<T> {
+        let mut result: Vec<T> = Vec::with_capacity(size);
+        for _ in 0..Self::MAX_ROUNDS {
+            if result.len() >= size {
+                break;
+            }
+            let candidates = self.initializer.initialize(size - result.len(), rng);
+            if candidates.is_empty() {
+                break;
+            }
+            for candidate in candidates {
+                if result.len() < size && !result.contains(&candidate) {
+                    result.push(candidate);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use genome::{BitStringConfig, RealVectorConfig};
+    use sim::SimRng;
+    use sim::types::F64;
+    use test::Test;
+
+    fn sphere() -> RealVectorConfig<F64> {
+        RealVectorConfig::new(vec![(-5.0, 5.0), (0.0, 1.0)],
+                              |v| F64(-v.values().iter().map(|x| x * x).sum::<f64>()))
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let mut init = FromFn::new(|rng: &mut SimRng| Test { f: rng.gen_range(0, 10) });
+        let population = init.initialize(20, &mut SimRng::new(0));
+        assert_eq!(population.len(), 20);
+        assert!(population.iter().all(|x| x.f >= 0 && x.f < 10));
+    }
+
+    #[test]
+    fn test_latin_hypercube() {
+        let config = sphere();
+        let population = LatinHypercube::new(config.clone()).initialize(10, &mut SimRng::new(0));
+        assert_eq!(population.len(), 10);
+        for (d, &(lo, hi)) in config.bounds().iter().enumerate() {
+            let mut strata: Vec<usize> = population.iter()
+                .map(|x| ((x.values()[d] - lo) / (hi - lo) * 10.0) as usize)
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..10).collect::<Vec<usize>>());
+        }
+    }
+
+    #[test]
+    fn test_seeded() {
+        let known = vec![Test { f: 42 }, Test { f: 43 }];
+        let mut init = Seeded::new(known, FromFn::new(|_: &mut SimRng| Test { f: 0 }));
+        let population = init.initialize(5, &mut SimRng::new(0));
+        let values: Vec<i64> = population.iter().map(|x| x.f).collect();
+        assert_eq!(values, vec![42, 43, 0, 0, 0]);
+        assert_eq!(init.initialize(1, &mut SimRng::new(0)).len(), 1);
+    }
+
+    #[test]
+    fn test_opposition_based() {
+        let config = sphere();
+        let x = config.from_values(vec![-4.0, 0.25]);
+        assert_eq!(x.opposite().values(), &[4.0, 0.75]);
+        let total = |population: &[RealVector<F64>]| {
+            population.iter().map(|x| x.fitness().value()).sum::<f64>()
+        };
+        let uniform = config.clone().initialize(20, &mut SimRng::new(0));
+        let opposition = OppositionBased::new(config).initialize(20, &mut SimRng::new(0));
+        assert_eq!(opposition.len(), 20);
+        // The fittest half of the candidates and their opposites beats the candidates alone.
+        assert!(total(&opposition) >= total(&uniform));
+    }
+
+    #[test]
+    fn test_deduplicated() {
+        let config = BitStringConfig::new(3, |b| b.count_ones());
+        let population = Deduplicated::new(config).initialize(8, &mut SimRng::new(0));
+        assert_eq!(population.len(), 8);
+        for (i, x) in population.iter().enumerate() {
+            assert!(!population[..i].contains(x));
+        }
+        // There are only 8 distinct bit strings of length 3.
+        let config = BitStringConfig::new(3, |b| b.count_ones());
+        let population = Deduplicated::new(config).initialize(10, &mut SimRng::new(0));
+        assert_eq!(population.len(), 8);
+    }
+}
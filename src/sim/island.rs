@@ -0,0 +1,655 @@
+// file: island.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains an island model implementation of `::sim::Simulation`,
+//! called a `Simulator`.
+//!
+//! To use a `Simulator`, you need a `SimulatorBuilder`, which you can
+//! obtain by calling `Simulator::builder()`. This `Simulator` always borrows its
+//! population: unlike `::sim::seq::Simulator`, it has no `owned_builder`.
+//!
+//! The population is split into several islands, which evolve independently
+//! like the population of a `::sim::seq::Simulator`: all islands share the same
+//! selector, replacement strategy and elitism. Every few iterations, each island sends
+//! migrants to its neighbours in the migration `Topology`. Migrants replace the worst
+//! individuals of the island they arrive on, keeping their cached fitness values.
+//!
+//! Keeping the islands apart preserves diversity, which helps against premature
+//! convergence on large problems.
+
+use pheno::{Fitness, Phenotype};
+use super::*;
+use super::select::*;
+use super::replace::*;
+use super::iterlimit::*;
+use super::earlystopper::*;
+use super::error::EMPTY_POPULATION;
+use std::mem;
+use std::time::Instant;
+
+/// Describes which islands send migrants to which other islands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Every island sends migrants to the next island.
+    /// The last island sends migrants to the first.
+    Ring,
+    /// Every island sends migrants to all other islands.
+    FullyConnected,
+    /// The first island is the hub: it sends migrants to all other islands,
+    /// and all other islands send migrants to it.
+    Star,
+    /// `Custom(edges)`: island `i` sends migrants to every island in `edges[i]`.
+    /// There should be an entry in `edges` for every island.
+    Custom(Vec<Vec<usize>>),
+}
+
+impl Topology {
+    /// Get the islands that `island` sends migrants to, in a model with `count` islands.
+    fn destinations(&self, island: usize, count: usize) -> Result<Vec<usize>, SimError> {
+        let destinations = match *self {
+            Topology::Ring => vec![(island + 1) % count],
+            Topology::FullyConnected => (0..count).collect(),
+            Topology::Star if island == 0 => (0..count).collect(),
+            Topology::Star => vec![0],
+            Topology::Custom(ref edges) => {
+                if edges.len() != count {
+                    return Err(SimError::InvalidTopology(format!("{} islands are connected, \
+                                                                  but there are {} islands.",
+                                                                 edges.len(),
+                                                                 count)));
+                }
+                if let Some(&d) = edges[island].iter().find(|&&d| d >= count) {
+                    return Err(SimError::InvalidTopology(format!("island {} sends migrants \
+                                                                  to island {}, but there are \
+                                                                  only {} islands.",
+                                                                 island,
+                                                                 d,
+                                                                 count)));
+                }
+                edges[island].clone()
+            }
+        };
+        Ok(destinations.into_iter().filter(|&d| d != island).collect())
+    }
+}
+
+/// An island model implementation of `::sim::Simulation`.
+#[derive(Debug)]
+pub struct Simulator<'a, T, F>
+    where T: 'a + Phenotype<F>,
+          F: Fitness
+{
+    target: &'a mut Vec<T>,
+    islands: Vec<Vec<Evaluated<T, F>>>,
+    num_islands: usize,
+    topology: Topology,
+    migration_interval: u64,
+    migrant_selector: Box<dyn Selector<T, F>>,
+    iter_limit: IterLimit,
+    selector: Box<dyn Selector<T, F>>,
+    replacement: Box<dyn Replacement<T, F>>,
+    earlystopper: Option<EarlyStopper<F>>,
+    elitism: usize,
+    /// The probability that a pair of parents creates its children by crossover.
+    crossover_rate: f64,
+    /// The probability that a child is mutated.
+    mutation_rate: f64,
+    history: Vec<Stats<F>>,
+    stop_reason: Option<StopReason>,
+    rng: SimRng,
+    duration: Option<NanoSecond>,
+    error: Option<SimError>,
+}
+
+impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    type B = SimulatorBuilder<'a, T, F>;
+
+    /// Create builder.
+    fn builder(population: &'a mut Vec<T>) -> SimulatorBuilder<'a, T, F> {
+        SimulatorBuilder {
+            sim: Simulator {
+                target: population,
+                islands: Vec::new(),
+                num_islands: 4,
+                topology: Topology::Ring,
+                migration_interval: 10,
+                migrant_selector: Box::new(MaximizeSelector::new(2)),
+                iter_limit: IterLimit::new(100),
+                selector: Box::new(MaximizeSelector::new(3)),
+                replacement: Box::new(StochasticReplacement::new()),
+                earlystopper: None,
+                elitism: 0,
+                crossover_rate: 1.0,
+                mutation_rate: 1.0,
+                history: Vec::new(),
+                stop_reason: None,
+                rng: SimRng::from_thread_rng(),
+                duration: Some(0),
+                error: None,
+            },
+        }
+    }
+
+    fn step(&mut self) -> StepResult {
+        if self.num_islands == 0 {
+            return self.fail(SimError::invalid_parameter("islands",
+                                                         self.num_islands,
+                                                         "Should be larger than zero."));
+        }
+        if self.islands.is_empty() || self.islands.iter().any(|x| x.is_empty()) {
+            let error = SimError::PopulationTooSmall {
+                size: self.individuals().count(),
+                minimum: self.num_islands.max(1),
+            };
+            return self.fail(error);
+        }
+        let invalid = self.individuals()
+            .enumerate()
+            .find(|&(_, x)| !x.fitness().is_valid())
+            .map(|(index, x)| SimError::invalid_fitness(index, x.fitness()));
+        if let Some(error) = invalid {
+            return self.fail(error);
+        }
+        let time_start = Instant::now();
+        if let Some(reason) = limit_reached(&self.iter_limit, &self.earlystopper) {
+            self.stop_reason = Some(reason);
+            return StepResult::Done;
+        }
+        let iteration = self.iter_limit.get();
+        let (crossover_rate, mutation_rate) = (self.crossover_rate, self.mutation_rate);
+        for island in &mut self.islands {
+            let result = seq::next_generation(island,
+                                              &*self.selector,
+                                              &*self.replacement,
+                                              self.elitism,
+                                              &mut self.rng,
+                                              |parents, rng| {
+                parents.iter()
+                    .flat_map(|(a, b)| {
+                        seq::Variation::draw(crossover_rate, mutation_rate, rng).breed(a, b)
+                    })
+                    .map(Evaluated::new)
+                    .collect()
+            });
+            if let Err(e) = result {
+                return self.fail(e);
+            }
+        }
+        self.iter_limit.inc();
+        if self.migration_interval > 0 && self.iter_limit.get() % self.migration_interval == 0 {
+            if let Err(e) = self.migrate() {
+                return self.fail(e);
+            }
+        }
+
+        if let Some(ref mut stopper) = self.earlystopper {
+            let highest_fitness = self.islands
+                .iter()
+                .flat_map(|x| x.iter())
+                .map(|x| x.fitness())
+                .max()
+                .unwrap()
+                .clone();
+            stopper.update(highest_fitness);
+        }
+        let stats = Stats::from_population(iteration, self.individuals(), elapsed_nanos(time_start));
+        self.history.extend(stats);
+        self.duration = match self.duration {
+            Some(x) => {
+                let elapsed = time_start.elapsed();
+                let y = elapsed.as_secs() as NanoSecond * 1_000_000_000 +
+                        i64::from(elapsed.subsec_nanos());
+                Some(x + y)
+            }
+            None => None,
+        };
+        StepResult::Success
+    }
+
+    #[allow(deprecated)]
+    fn checked_step(&mut self) -> StepResult {
+        if self.error.is_some() {
+            panic!("Attemped to step a Simulator after an error!")
+        } else {
+            self.step()
+        }
+    }
+
+    #[allow(deprecated)]
+    fn run(&mut self) -> RunResult {
+        // Loop until Failure or Done.
+        loop {
+            match self.step() {
+                StepResult::Success => {}
+                StepResult::Failure => return RunResult::Failure,
+                StepResult::Done => return RunResult::Done,
+            }
+        }
+    }
+
+    fn get(&self) -> SimResult<'_, T> {
+        match self.error {
+            Some(ref e) => Err(e),
+            None => {
+                self.individuals()
+                    .max_by(|x, y| x.fitness().cmp(y.fitness()))
+                    .map(|x| x.pheno())
+                    .ok_or(&EMPTY_POPULATION)
+            }
+        }
+    }
+
+    fn iterations(&self) -> u64 {
+        self.iter_limit.get()
+    }
+
+    fn time(&self) -> Option<NanoSecond> {
+        self.duration
+    }
+
+    fn population(&self) -> Vec<T> {
+        self.individuals().map(|x| x.pheno().clone()).collect()
+    }
+
+    fn history(&self) -> &[Stats<F>] {
+        &self.history
+    }
+
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.stop_reason.as_ref()
+    }
+}
+
+impl<'a, T, F> Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Record `error` as the reason why the simulation terminated.
+    fn fail(&mut self, error: SimError) -> StepResult {
+        self.stop_reason = Some(StopReason::Failure(error.clone()));
+        self.error = Some(error);
+        StepResult::Failure
+    }
+
+    /// Get the populations of all islands.
+    pub fn islands(&self) -> Vec<Vec<T>> {
+        self.islands
+            .iter()
+            .map(|island| island.iter().map(|x| x.pheno().clone()).collect())
+            .collect()
+    }
+
+    /// Iterate over the individuals of all islands.
+    fn individuals(&self) -> impl Iterator<Item = &Evaluated<T, F>> {
+        self.islands.iter().flat_map(|x| x.iter())
+    }
+
+    /// Send migrants from every island to its neighbours in the topology.
+    ///
+    /// All migrants are selected before any of them arrive, so that no
+    /// individual migrates twice in the same round.
+    fn migrate(&mut self) -> Result<(), SimError> {
+        let count = self.islands.len();
+        let mut outgoing: Vec<(usize, Vec<Evaluated<T, F>>)> = Vec::new();
+        for (i, island) in self.islands.iter().enumerate() {
+            let destinations = self.topology.destinations(i, count)?;
+            if destinations.is_empty() {
+                continue;
+            }
+            let migrants: Vec<Evaluated<T, F>> = self.migrant_selector
+                .select_evaluated(island, &mut self.rng)?
+                .into_iter()
+                .flat_map(|(a, b)| vec![a, b])
+                .collect();
+            for destination in destinations {
+                outgoing.push((destination, migrants.clone()));
+            }
+        }
+        for (destination, migrants) in outgoing {
+            let island = mem::take(&mut self.islands[destination]);
+            self.islands[destination] = WorstReplacement::new()
+                .replace(island, migrants, &mut self.rng)?;
+        }
+        Ok(())
+    }
+}
+
+/// A `Builder` for the `Simulator` type.
+#[derive(Debug)]
+pub struct SimulatorBuilder<'a, T, F>
+    where T: 'a + Phenotype<F>,
+          F: Fitness
+{
+    sim: Simulator<'a, T, F>,
+}
+
+impl<'a, T, F> SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Set the number of islands. The population is divided over the islands
+    /// as evenly as possible, so it should contain at least one individual per island.
+    ///
+    /// By default, 4 islands are used. If `n` is zero, the first step fails, and the
+    /// population stays in the `Vec` that was passed to `builder`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_islands(mut self, n: usize) -> Self {
+        self.sim.num_islands = n;
+        self
+    }
+
+    /// Set the migration topology. By default, `Topology::Ring` is used.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_topology(mut self, topology: Topology) -> Self {
+        self.sim.topology = topology;
+        self
+    }
+
+    /// Set the number of iterations between two migrations.
+    /// If `i` is zero, no migration takes place. By default, islands migrate every 10 iterations.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_migration_interval(mut self, i: u64) -> Self {
+        self.sim.migration_interval = i;
+        self
+    }
+
+    /// Set the selector that chooses the migrants of every island.
+    ///
+    /// The migrants keep their cached fitness values if the selector implements
+    /// `Selector::select_evaluated`, like all selectors of the `select` module do.
+    /// All individuals in the selected parents migrate, so the number of migrants
+    /// is the `count` of the selector. For example, `MaximizeSelector::new(4)` sends
+    /// the 4 best individuals, while `StochasticSelector::new(4)` sends 4 random individuals.
+    /// By default, the 2 best individuals of every island migrate.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_migrant_selector(mut self, sel: Box<dyn Selector<T, F>>) -> Self {
+        self.sim.migrant_selector = sel;
+        self
+    }
+
+    /// Set the selector that every island uses to select parents.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_selector(mut self, sel: Box<dyn Selector<T, F>>) -> Self {
+        self.sim.selector = sel;
+        self
+    }
+
+    /// Set the replacement strategy that every island uses.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_replacement(mut self, replacement: Box<dyn Replacement<T, F>>) -> Self {
+        self.sim.replacement = replacement;
+        self
+    }
+
+    /// Set the maximum number of iterations of the resulting `Simulator`.
+    ///
+    /// The `Simulator` will stop running after this number of iterations.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_max_iters(mut self, i: u64) -> Self {
+        self.sim.iter_limit = IterLimit::new(i);
+        self
+    }
+
+    /// Set early stopping. If for `n_iters` iterations, the change in the highest fitness
+    /// of all islands is smaller than `delta`, the simulator will stop running.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_early_stop(mut self, delta: F, n_iters: u64) -> Self {
+        self.sim.earlystopper = Some(EarlyStopper::new(delta, n_iters));
+        self
+    }
+
+    /// Set elitism. The `n` best phenotypes of every island are guaranteed to survive
+    /// every step.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_elitism(mut self, n: usize) -> Self {
+        self.sim.elitism = n;
+        self
+    }
+
+    /// Set the probability that a pair of parents creates its children by crossover.
+    /// See `seq::SimulatorBuilder::set_crossover_rate` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover_rate(mut self, rate: f64) -> Self {
+        self.sim.crossover_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the probability that a child is mutated after crossover.
+    /// See `seq::SimulatorBuilder::set_mutation_rate` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.sim.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the random number generator of the resulting `Simulator`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_rng(mut self, rng: SimRng) -> Self {
+        self.sim.rng = rng;
+        self
+    }
+
+    /// Seed the random number generator of the resulting `Simulator`.
+    ///
+    /// The built-in genomes draw their random numbers from this generator, so runs
+    /// with them are reproducible. A `Phenotype` that uses `rand::thread_rng` is not.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.sim.rng = SimRng::new(seed);
+        self
+    }
+}
+
+impl<'a, T, F> Builder<Simulator<'a, T, F>> for SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn build(mut self) -> Simulator<'a, T, F> {
+        if self.sim.num_islands == 0 {
+            // There is nowhere to put the population, so leave it with its owner.
+            return self.sim;
+        }
+        let individuals: Vec<T> = self.sim.target.drain(..).collect();
+        let fitnesses = evaluate_all(&individuals);
+        let mut population = zip_evaluated(individuals, fitnesses).into_iter();
+        let n = self.sim.num_islands;
+        let total = population.len();
+        self.sim.islands = (0..n)
+            .map(|i| {
+                // The first `total % n` islands get one extra individual.
+                let size = total / n + usize::from(i < total % n);
+                population.by_ref().take(size).collect()
+            })
+            .collect();
+        self.sim
+    }
+}
+
+impl<'a, T, F> Drop for Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn drop(&mut self) {
+        // Hand the final population back to the owner of `target`.
+        for island in self.islands.drain(..) {
+            self.target.extend(island.into_iter().map(Evaluated::into_pheno));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::*;
+    use ::sim::select::*;
+    use ::sim::island::Topology;
+    use test::{MyFitness, Test};
+    use std::cell::Cell;
+
+    thread_local!(static EVALUATIONS: Cell<usize> = const { Cell::new(0) });
+
+    /// A `Phenotype` that counts how often its fitness is evaluated.
+    #[derive(Clone, Copy)]
+    struct Counted {
+        f: i64,
+    }
+
+    impl Phenotype<MyFitness> for Counted {
+        fn fitness(&self) -> MyFitness {
+            EVALUATIONS.with(|e| e.set(e.get() + 1));
+            MyFitness { f: self.f }
+        }
+
+        fn crossover(&self, other: &Counted) -> Counted {
+            Counted { f: (self.f + other.f) / 2 }
+        }
+
+        fn mutate(&self) -> Counted {
+            *self
+        }
+    }
+
+    #[test]
+    fn test_destinations() {
+        assert_eq!(Topology::Ring.destinations(3, 4), Ok(vec![0]));
+        assert_eq!(Topology::FullyConnected.destinations(1, 3), Ok(vec![0, 2]));
+        assert_eq!(Topology::Star.destinations(0, 3), Ok(vec![1, 2]));
+        assert_eq!(Topology::Star.destinations(2, 3), Ok(vec![0]));
+        assert_eq!(Topology::Ring.destinations(0, 1), Ok(vec![]));
+        let custom = Topology::Custom(vec![vec![1, 2], vec![], vec![0]]);
+        assert_eq!(custom.destinations(0, 3), Ok(vec![1, 2]));
+        assert_eq!(custom.destinations(1, 3), Ok(vec![]));
+        assert!(custom.destinations(0, 4).is_err());
+        assert!(Topology::Custom(vec![vec![3]]).destinations(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_islands_split_evenly() {
+        let mut population: Vec<Test> = (0..10).map(|i| Test { f: i }).collect();
+        let s = island::Simulator::builder(&mut population).set_islands(3).build();
+        let sizes: Vec<usize> = s.islands().iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn test_migration_ring() {
+        let mut population: Vec<Test> = (0..40).map(|i| Test { f: i }).collect();
+        let mut s = island::Simulator::builder(&mut population).build();
+        s.migrate().unwrap();
+        let islands = s.islands();
+        // The two best individuals of the last island arrive on the first island,
+        // replacing its two worst individuals.
+        let mut first: Vec<i64> = islands[0].iter().map(|x| x.f).collect();
+        first.sort_unstable();
+        assert_eq!(first, vec![2, 3, 4, 5, 6, 7, 8, 9, 38, 39]);
+        assert!(islands.iter().all(|x| x.len() == 10));
+    }
+
+    #[test]
+    fn test_migration_keeps_fitness() {
+        let mut population: Vec<Counted> = (0..40).map(|i| Counted { f: i }).collect();
+        let mut s = island::Simulator::builder(&mut population)
+            .set_topology(Topology::FullyConnected)
+            .build();
+        let before = EVALUATIONS.with(|e| e.get());
+        s.migrate().unwrap();
+        assert_eq!(EVALUATIONS.with(|e| e.get()), before);
+    }
+
+    #[test]
+    fn test_run() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        {
+            let mut s = island::Simulator::builder(&mut population)
+                .set_islands(5)
+                .set_topology(Topology::FullyConnected)
+                .set_selector(Box::new(TournamentSelector::new(4, 3)))
+                .set_migration_interval(2)
+                .set_max_iters(20)
+                .set_seed(0)
+                .build();
+            assert_eq!(s.run(), RunResult::Done);
+            assert_eq!(s.iterations(), 20);
+            assert_eq!(s.stop_reason(), Some(&StopReason::MaxIterations));
+            assert!(s.get().is_ok());
+        }
+        assert_eq!(population.len(), 100);
+    }
+
+    #[test]
+    fn test_too_many_islands() {
+        let mut population: Vec<Test> = (0..3).map(|i| Test { f: i }).collect();
+        let mut s = island::Simulator::builder(&mut population).set_islands(4).build();
+        assert_eq!(s.run(), RunResult::Failure);
+        assert!(s.get().is_err());
+    }
+
+    #[test]
+    fn test_zero_islands() {
+        let mut population: Vec<Test> = (0..10).map(|i| Test { f: i }).collect();
+        {
+            let mut s = island::Simulator::builder(&mut population).set_islands(0).build();
+            assert_eq!(s.run(), RunResult::Failure);
+            match s.get() {
+                Err(&SimError::InvalidParameter { name, .. }) => assert_eq!(name, "islands"),
+                _ => panic!("Expected an invalid number of islands."),
+            }
+        }
+        assert_eq!(population.len(), 10);
+    }
+
+    #[test]
+    fn test_crossover_rate() {
+        let mut population: Vec<Counted> = (0..40).map(|i| Counted { f: 1 << i }).collect();
+        let mut s = island::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(4, 3)))
+            .set_crossover_rate(0.0)
+            .set_max_iters(20)
+            .set_seed(1)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        // Without crossover, the children are clones of their parents, so no averages
+        // of two different powers of two appear.
+        assert!(s.population().iter().all(|x| x.f.count_ones() == 1));
+    }
+
+    #[test]
+    fn test_migration_error_propagate() {
+        let mut population: Vec<Test> = (0..40).map(|i| Test { f: i }).collect();
+        let mut s = island::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(2, 2)))
+            .set_migrant_selector(Box::new(MaximizeSelector::new(0)))
+            .set_migration_interval(1)
+            .build();
+        assert_eq!(s.run(), RunResult::Failure);
+        assert!(s.get().is_err());
+    }
+}
@@ -16,6 +16,7 @@
 
 /// An iteration limiter.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct IterLimit {
     /// Maximum number of iterations allowed.
     max: u64,
@@ -44,6 +45,11 @@ impl IterLimit {
         self.cur = 0;
     }
 
+    /// Set the current number of iterations.
+    pub fn set(&mut self, cur: u64) {
+        self.cur = cur;
+    }
+
     /// Get the current number of iterations.
     pub fn get(&self) -> u64 {
         self.cur
@@ -55,6 +61,7 @@ mod tests {
     use super::IterLimit;
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_iter_limit_reset() {
         let mut limit = IterLimit::new(5);
         for _ in 0..4 {
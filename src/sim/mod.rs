@@ -17,10 +17,33 @@
 use pheno::{Fitness, Phenotype};
 
 pub mod seq;
+pub mod par;
+pub mod nsga2;
+pub mod island;
+pub mod pareto;
 pub mod select;
+pub mod replace;
+pub mod observer;
+pub mod stats;
+pub mod checkpoint;
+pub mod stop;
+pub mod init;
 pub mod types;
 mod iterlimit;
 mod earlystopper;
+mod rng;
+mod evaluated;
+mod error;
+
+pub use self::rng::SimRng;
+pub(crate) use self::rng::LocalRng;
+pub use self::evaluated::Evaluated;
+pub use self::error::SimError;
+use self::evaluated::{evaluate_all, zip_evaluated};
+use self::stats::Stats;
+use self::iterlimit::IterLimit;
+use self::earlystopper::EarlyStopper;
+use std::time::Instant;
 
 /// A `Builder` can create new instances of an object.
 /// For this library, only `Simulation` objects use this `Builder`.
@@ -32,8 +55,8 @@ pub trait Builder<T: ?Sized> {
 /// Simulation run time is defined in nanoseconds.
 pub type NanoSecond = i64;
 /// The result of a simulation, containing the best phenotype
-/// or an error message.
-pub type SimResult<'a, T> = Result<&'a T, &'a str>;
+/// or the error that occurred.
+pub type SimResult<'a, T> = Result<&'a T, &'a SimError>;
 
 /// The result of running a single step.
 #[derive(Clone, Copy, PartialEq,Eq,Debug)]
@@ -55,6 +78,22 @@ pub enum RunResult {
     Done,
 }
 
+/// The reason why a simulation terminated.
+#[derive(Clone, PartialEq, Debug)]
+pub enum StopReason {
+    /// The maximum number of iterations was reached.
+    MaxIterations,
+    /// Early stopping triggered: the highest fitness did not improve enough
+    /// for a number of iterations.
+    EarlyStop,
+    /// The stop criterion set with `set_stop_criterion` was met.
+    Criterion,
+    /// An observer requested to stop.
+    Observer,
+    /// An error occurred.
+    Failure(SimError),
+}
+
 /// A `Simulation` is an execution of a genetic algorithm.
 pub trait Simulation<'a, T, F>
     where T: Phenotype<F>,
@@ -95,12 +134,14 @@ pub trait Simulation<'a, T, F>
     /// Get the result of the latest step or of a complete run.
     ///
     /// This function will either return the best performing individual,
-    /// or an error string indicating what went wrong.
-    fn get(&'a self) -> SimResult<'a, T>;
+    /// or the `SimError` indicating what went wrong. An empty population results in
+    /// `SimError::PopulationTooSmall`.
+    fn get(&self) -> SimResult<'_, T>;
     /// Get the number of nanoseconds spent running, or `None` in case of an overflow.
     ///
     /// When `Self` is `par::Simulator`, i.e. a parallel simulator is used,
-    /// the duration is the average duration of all child simulators.
+    /// this is the wall-clock time spent in its steps, not the sum of the time
+    /// spent on every thread.
     fn time(&self) -> Option<NanoSecond>;
     /// Get the number of iterations the `Simulator` has executed so far.
     ///
@@ -112,4 +153,37 @@ pub trait Simulation<'a, T, F>
     /// Using this function clones the population out of the `Simulation`, so use
     /// it sparingly.
     fn population(&self) -> Vec<T>;
+    /// Get the statistics of every generation so far, in order.
+    ///
+    /// Simulators that do not record statistics return an empty slice.
+    fn history(&self) -> &[Stats<F>] {
+        &[]
+    }
+    /// Get the reason why the simulation terminated, or `None` if it has not
+    /// terminated yet.
+    ///
+    /// Simulators that do not record the reason return `None`.
+    fn stop_reason(&self) -> Option<&StopReason> {
+        None
+    }
+}
+
+/// Returns why a simulation should stop according to `iter_limit` and `earlystopper`,
+/// or `None` if it should not stop.
+fn limit_reached<F: Fitness>(iter_limit: &IterLimit,
+                             earlystopper: &Option<EarlyStopper<F>>)
+                             -> Option<StopReason> {
+    if iter_limit.reached() {
+        Some(StopReason::MaxIterations)
+    } else if earlystopper.as_ref().is_some_and(|x| x.reached()) {
+        Some(StopReason::EarlyStop)
+    } else {
+        None
+    }
+}
+
+/// Get the number of nanoseconds that passed since `start`.
+fn elapsed_nanos(start: Instant) -> NanoSecond {
+    let elapsed = start.elapsed();
+    elapsed.as_secs() as NanoSecond * 1_000_000_000 + i64::from(elapsed.subsec_nanos())
 }
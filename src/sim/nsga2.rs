@@ -0,0 +1,467 @@
+// file: nsga2.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains a multi-objective implementation of `::sim::Simulation`,
+//! called a `Simulator`, based on NSGA-II
+//! (Deb et al., *A fast and elitist multiobjective genetic algorithm: NSGA-II*, 2002).
+//!
+//! To use a `Simulator`, you need a `SimulatorBuilder`, which you can
+//! obtain by calling `Simulator::builder()`. This `Simulator` always borrows its
+//! population: unlike `::sim::seq::Simulator`, it has no `owned_builder`.
+//!
+//! The fitness type of the phenotypes must implement `MultiFitness`.
+//! In every step, as many children are created as there are individuals in the population.
+//! Parents are chosen with binary tournaments using the crowded-comparison operator.
+//! The next population is then chosen from parents and children together, front by
+//! front, using crowding distance to break ties in the last front that fits.
+//!
+//! Use `pareto_front()` to get all non-dominated individuals. `get()` returns
+//! only one of them: the greatest according to the `Ord` implementation of the fitness type.
+
+use pheno::{MultiFitness, Phenotype};
+use rand::Rng;
+use super::*;
+use super::iterlimit::*;
+use super::pareto::*;
+use super::error::EMPTY_POPULATION;
+use std::cmp::Ordering;
+use std::time::Instant;
+
+/// A multi-objective implementation of `::sim::Simulation`, using NSGA-II.
+#[derive(Debug)]
+pub struct Simulator<'a, T, F>
+    where T: 'a + Phenotype<F>,
+          F: MultiFitness
+{
+    target: &'a mut Vec<T>,
+    population: Vec<Evaluated<T, F>>,
+    /// The index of the front of every individual in `population`.
+    ranks: Vec<usize>,
+    /// The crowding distance of every individual in `population`.
+    distances: Vec<f64>,
+    iter_limit: IterLimit,
+    history: Vec<Stats<F>>,
+    stop_reason: Option<StopReason>,
+    /// The probability that a pair of parents creates its children by crossover.
+    crossover_rate: f64,
+    /// The probability that a child is mutated.
+    mutation_rate: f64,
+    rng: SimRng,
+    duration: Option<NanoSecond>,
+    error: Option<SimError>,
+}
+
+impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: MultiFitness
+{
+    type B = SimulatorBuilder<'a, T, F>;
+
+    /// Create builder.
+    fn builder(population: &'a mut Vec<T>) -> SimulatorBuilder<'a, T, F> {
+        SimulatorBuilder {
+            sim: Simulator {
+                target: population,
+                population: Vec::new(),
+                ranks: Vec::new(),
+                distances: Vec::new(),
+                iter_limit: IterLimit::new(100),
+                history: Vec::new(),
+                stop_reason: None,
+                crossover_rate: 1.0,
+                mutation_rate: 1.0,
+                rng: SimRng::from_thread_rng(),
+                duration: Some(0),
+                error: None,
+            },
+        }
+    }
+
+    fn step(&mut self) -> StepResult {
+        if self.population.len() < 2 {
+            return self.fail(SimError::PopulationTooSmall {
+                size: self.population.len(),
+                minimum: 2,
+            });
+        }
+        if let Some(index) = self.population.iter().position(|x| !x.fitness().is_valid()) {
+            let error = SimError::invalid_fitness(index, self.population[index].fitness());
+            return self.fail(error);
+        }
+        if self.iter_limit.reached() {
+            self.stop_reason = Some(StopReason::MaxIterations);
+            return StepResult::Done;
+        }
+        let time_start = Instant::now();
+
+        let iteration = self.iter_limit.get();
+        let size = self.population.len();
+        let mut children = Vec::with_capacity(size);
+        while children.len() < size {
+            let a = self.tournament();
+            let b = self.tournament();
+            let variation = seq::Variation::draw(self.crossover_rate,
+                                                 self.mutation_rate,
+                                                 &mut self.rng);
+            let offspring = variation.breed(self.population[a].pheno(),
+                                            self.population[b].pheno());
+            assert!(!offspring.is_empty(), "A pair of parents created no children.");
+            let missing = size - children.len();
+            children.extend(offspring.into_iter().take(missing).map(Evaluated::new));
+        }
+        let mut combined: Vec<Evaluated<T, F>> = self.population.drain(..).collect();
+        combined.append(&mut children);
+        self.survive(combined, size);
+        let stats = Stats::from_population(iteration, &self.population, elapsed_nanos(time_start));
+        self.history.extend(stats);
+
+        self.iter_limit.inc();
+        self.duration = match self.duration {
+            Some(x) => {
+                let elapsed = time_start.elapsed();
+                let y = elapsed.as_secs() as NanoSecond * 1_000_000_000 +
+                        i64::from(elapsed.subsec_nanos());
+                Some(x + y)
+            }
+            None => None,
+        };
+        StepResult::Success
+    }
+
+    #[allow(deprecated)]
+    fn checked_step(&mut self) -> StepResult {
+        if self.error.is_some() {
+            panic!("Attemped to step a Simulator after an error!")
+        } else {
+            self.step()
+        }
+    }
+
+    #[allow(deprecated)]
+    fn run(&mut self) -> RunResult {
+        // Loop until Failure or Done.
+        loop {
+            match self.step() {
+                StepResult::Success => {}
+                StepResult::Failure => return RunResult::Failure,
+                StepResult::Done => return RunResult::Done,
+            }
+        }
+    }
+
+    fn get(&self) -> SimResult<'_, T> {
+        match self.error {
+            Some(ref e) => Err(e),
+            None => {
+                self.population
+                    .iter()
+                    .zip(&self.ranks)
+                    .filter(|&(_, &rank)| rank == 0)
+                    .map(|(x, _)| x)
+                    .max_by(|x, y| x.fitness().cmp(y.fitness()))
+                    .map(|x| x.pheno())
+                    .ok_or(&EMPTY_POPULATION)
+            }
+        }
+    }
+
+    fn iterations(&self) -> u64 {
+        self.iter_limit.get()
+    }
+
+    fn time(&self) -> Option<NanoSecond> {
+        self.duration
+    }
+
+    fn population(&self) -> Vec<T> {
+        self.population.iter().map(|x| x.pheno().clone()).collect()
+    }
+
+    fn history(&self) -> &[Stats<F>] {
+        &self.history
+    }
+
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.stop_reason.as_ref()
+    }
+}
+
+impl<'a, T, F> Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: MultiFitness
+{
+    /// Get the current Pareto front: all individuals in the population that are
+    /// not dominated by any other individual.
+    pub fn pareto_front(&self) -> Vec<&T> {
+        self.population
+            .iter()
+            .zip(&self.ranks)
+            .filter(|&(_, &rank)| rank == 0)
+            .map(|(x, _)| x.pheno())
+            .collect()
+    }
+
+    /// Record `error` as the reason why the simulation terminated.
+    fn fail(&mut self, error: SimError) -> StepResult {
+        self.stop_reason = Some(StopReason::Failure(error.clone()));
+        self.error = Some(error);
+        StepResult::Failure
+    }
+
+    /// Run a binary tournament using the crowded-comparison operator,
+    /// returning the index of the winner.
+    fn tournament(&mut self) -> usize {
+        let a = self.rng.gen_range::<usize>(0, self.population.len());
+        let b = self.rng.gen_range::<usize>(0, self.population.len());
+        match crowded_cmp((self.ranks[a], self.distances[a]),
+                          (self.ranks[b], self.distances[b])) {
+            Ordering::Less => b,
+            _ => a,
+        }
+    }
+
+    /// Choose `size` survivors from `combined`, front by front, and store them
+    /// as the new population together with their ranks and crowding distances.
+    fn survive(&mut self, combined: Vec<Evaluated<T, F>>, size: usize) {
+        let (survivors, ranks, distances) = {
+            let fitnesses: Vec<&F> = combined.iter().map(|x| x.fitness()).collect();
+            let mut survivors: Vec<usize> = Vec::with_capacity(size);
+            let mut ranks: Vec<usize> = Vec::with_capacity(size);
+            let mut distances: Vec<f64> = Vec::with_capacity(size);
+            for (rank, front) in non_dominated_sort(&fitnesses).into_iter().enumerate() {
+                if survivors.len() >= size {
+                    break;
+                }
+                let front_distances = crowding_distance(&fitnesses, &front);
+                let mut members: Vec<(usize, f64)> = front.into_iter()
+                    .zip(front_distances)
+                    .collect();
+                if survivors.len() + members.len() > size {
+                    // Only the least crowded members of the last front survive.
+                    members.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+                    members.truncate(size - survivors.len());
+                }
+                for (index, distance) in members {
+                    survivors.push(index);
+                    ranks.push(rank);
+                    distances.push(distance);
+                }
+            }
+            (survivors, ranks, distances)
+        };
+        let mut combined: Vec<Option<Evaluated<T, F>>> = combined.into_iter().map(Some).collect();
+        self.population = survivors.into_iter()
+            .map(|i| combined[i].take().unwrap())
+            .collect();
+        self.ranks = ranks;
+        self.distances = distances;
+    }
+}
+
+/// A `Builder` for the `Simulator` type.
+#[derive(Debug)]
+pub struct SimulatorBuilder<'a, T, F>
+    where T: 'a + Phenotype<F>,
+          F: MultiFitness
+{
+    sim: Simulator<'a, T, F>,
+}
+
+impl<'a, T, F> SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F>,
+          F: MultiFitness
+{
+    /// Set the maximum number of iterations of the resulting `Simulator`.
+    ///
+    /// The `Simulator` will stop running after this number of iterations.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_max_iters(mut self, i: u64) -> Self {
+        self.sim.iter_limit = IterLimit::new(i);
+        self
+    }
+
+    /// Set the probability that a pair of parents creates its children by crossover.
+    /// See `seq::SimulatorBuilder::set_crossover_rate` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover_rate(mut self, rate: f64) -> Self {
+        self.sim.crossover_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the probability that a child is mutated after crossover.
+    /// See `seq::SimulatorBuilder::set_mutation_rate` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.sim.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the random number generator of the resulting `Simulator`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_rng(mut self, rng: SimRng) -> Self {
+        self.sim.rng = rng;
+        self
+    }
+
+    /// Seed the random number generator of the resulting `Simulator`.
+    ///
+    /// The built-in genomes draw their random numbers from this generator, so runs
+    /// with them are reproducible. A `Phenotype` that uses `rand::thread_rng` is not.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.sim.rng = SimRng::new(seed);
+        self
+    }
+}
+
+impl<'a, T, F> Builder<Simulator<'a, T, F>> for SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F>,
+          F: MultiFitness
+{
+    fn build(mut self) -> Simulator<'a, T, F> {
+        let population: Vec<Evaluated<T, F>> = self.sim
+            .target
+            .drain(..)
+            .map(Evaluated::new)
+            .collect();
+        // Everyone survives, this only computes the ranks and crowding distances.
+        let size = population.len();
+        self.sim.survive(population, size);
+        self.sim
+    }
+}
+
+impl<'a, T, F> Drop for Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: MultiFitness
+{
+    fn drop(&mut self) {
+        // Hand the final population back to the owner of `target`.
+        self.target.extend(self.population.drain(..).map(Evaluated::into_pheno));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::*;
+    use ::sim::pareto::dominates;
+    use ::sim::types::Objectives;
+
+    /// Schaffer's first problem: minimise x^2 and (x - 2)^2.
+    /// The Pareto optimal set is 0 <= x <= 2.
+    #[derive(Clone, Copy, Debug)]
+    struct Schaffer {
+        x: f64,
+    }
+
+    impl Phenotype<Objectives> for Schaffer {
+        fn fitness(&self) -> Objectives {
+            Objectives(vec![-self.x * self.x, -(self.x - 2.0) * (self.x - 2.0)])
+        }
+
+        fn crossover(&self, other: &Schaffer) -> Schaffer {
+            Schaffer { x: (self.x + other.x) / 2.0 }
+        }
+
+        fn mutate(&self) -> Schaffer {
+            Schaffer { x: self.x * 0.9 + 0.1 }
+        }
+    }
+
+    #[test]
+    fn test_front_is_non_dominated() {
+        let mut population: Vec<Schaffer> = (-20..20)
+            .map(|i| Schaffer { x: f64::from(i) })
+            .collect();
+        let mut s = nsga2::Simulator::builder(&mut population)
+            .set_max_iters(30)
+            .set_seed(3)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert_eq!(s.stop_reason(), Some(&StopReason::MaxIterations));
+        assert_eq!(s.population().len(), 40);
+        let front = s.pareto_front();
+        assert!(!front.is_empty());
+        let population = s.population();
+        for a in &front {
+            assert!(a.x >= 0.0 && a.x <= 2.0);
+            for b in &population {
+                assert!(!dominates(&b.fitness(), &a.fitness()));
+            }
+        }
+        let best = s.get().unwrap();
+        assert!(front.iter().any(|x| x.x == best.x));
+    }
+
+    #[test]
+    fn test_nan_objectives() {
+        let mut population: Vec<Schaffer> = (0..10).map(|i| Schaffer { x: f64::from(i) }).collect();
+        population[3].x = f64::NAN;
+        let mut s = nsga2::Simulator::builder(&mut population).build();
+        assert_eq!(s.run(), RunResult::Failure);
+        match s.get() {
+            Err(&SimError::InvalidFitness { index, .. }) => {
+                assert!(s.population()[index].x.is_nan())
+            }
+            _ => panic!("Expected an invalid fitness."),
+        }
+    }
+
+    #[test]
+    fn test_too_small_population() {
+        let mut population = vec![Schaffer { x: 1.0 }];
+        let mut s = nsga2::Simulator::builder(&mut population).build();
+        assert_eq!(s.run(), RunResult::Failure);
+        let error = SimError::PopulationTooSmall {
+            size: 1,
+            minimum: 2,
+        };
+        assert_eq!(s.get().err(), Some(&error));
+        assert_eq!(s.stop_reason(), Some(&StopReason::Failure(error.clone())));
+    }
+
+    #[test]
+    fn test_rates() {
+        let initial: Vec<f64> = (-20..20).map(f64::from).collect();
+        let mut population: Vec<Schaffer> = initial.iter().map(|&x| Schaffer { x }).collect();
+        {
+            let mut s = nsga2::Simulator::builder(&mut population)
+                .set_crossover_rate(0.0)
+                .set_mutation_rate(0.0)
+                .set_max_iters(10)
+                .build();
+            assert_eq!(s.run(), RunResult::Done);
+        }
+        // Without crossover and mutation, the children are copies of their parents.
+        assert!(population.iter().all(|s| initial.contains(&s.x)));
+    }
+
+    #[test]
+    fn test_get_empty_population() {
+        let mut population: Vec<Schaffer> = Vec::new();
+        let s = nsga2::Simulator::builder(&mut population).build();
+        let error = SimError::PopulationTooSmall {
+            size: 0,
+            minimum: 1,
+        };
+        assert_eq!(s.get().err(), Some(&error));
+    }
+}
@@ -0,0 +1,63 @@
+// file: observer.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The observer module provides a trait that can be implemented to watch
+//! the progress of a `Simulation`, without writing the step loop yourself.
+//!
+//! Observers are registered with `add_observer` on the `SimulatorBuilder` of
+//! `seq::Simulator` or `par::Simulator`. The island and NSGA-II simulators do not
+//! support observers.
+//! Every callback has an empty default implementation, so you only need to implement
+//! the ones you are interested in. An observer can abort a run by returning `true`
+//! from `stop_requested`.
+//!
+//! The simulator owns its observers. To get data out of an observer after the run,
+//! share it with the observer, for example through an `Arc<Mutex<_>>`. Observers need to be
+//! `Send`, so that a simulator can be moved to another thread.
+
+use pheno::{Fitness, Phenotype};
+use sim::{Evaluated, StopReason};
+use sim::select::Parents;
+use std::fmt::Debug;
+
+/// An `Observer` is notified of the progress of a `Simulation`.
+///
+/// `iteration` is the index of the current generation, starting at zero.
+pub trait Observer<T, F>: Debug + Send
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Called at the start of every generation, with the current population.
+    fn on_generation_start(&mut self, _iteration: u64, _population: &[Evaluated<T, F>]) {}
+
+    /// Called after the parents of a generation have been selected.
+    fn on_selection(&mut self, _iteration: u64, _parents: &Parents<T>) {}
+
+    /// Called at the end of every generation, with the new population.
+    fn on_generation_end(&mut self, _iteration: u64, _population: &[Evaluated<T, F>]) {}
+
+    /// Called at the end of a generation if its best individual is better than
+    /// the best individual of all previous generations.
+    fn on_new_best(&mut self, _iteration: u64, _best: &Evaluated<T, F>) {}
+
+    /// Called once, when the simulation finishes or fails, with the reason why.
+    fn on_termination(&mut self, _iteration: u64, _reason: &StopReason) {}
+
+    /// Returns whether the simulation should stop before starting the next generation.
+    fn stop_requested(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,477 @@
+// file: par.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains a parallel implementation of `::sim::Simulation`,
+//! called a `Simulator`.
+//!
+//! To use a `Simulator`, you need a `SimulatorBuilder`, which you can
+//! obtain by calling `Simulator::builder()`.
+//!
+//! This `Simulator` behaves exactly like `::sim::seq::Simulator`, but
+//! spreads the evaluation of fitness values and the creation of children
+//! (crossover and mutation) over several threads. Selection is still performed
+//! on the calling thread. Your `Phenotype` needs to be `Send` and `Sync`,
+//! and your `Fitness` type needs to be `Send`.
+
+use pheno::Phenotype;
+use pheno::Fitness;
+use super::*;
+use super::select::*;
+use super::replace::*;
+use super::observer::*;
+use super::checkpoint::Checkpoint;
+use super::stop::StopCriterion;
+use super::init::Initializer;
+use std::panic;
+use std::thread;
+
+/// A parallel implementation of `::sim::Simulation`.
+/// The expensive parts of the genetic algorithm are run on multiple threads.
+#[derive(Debug)]
+pub struct Simulator<'a, T, F>
+    where T: 'a + Phenotype<F> + Send + Sync,
+          F: Fitness + Send
+{
+    sim: seq::Simulator<'a, T, F>,
+    threads: usize,
+}
+
+impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
+    where T: Phenotype<F> + Send + Sync,
+          F: Fitness + Send
+{
+    type B = SimulatorBuilder<'a, T, F>;
+
+    /// Create builder.
+    fn builder(population: &'a mut Vec<T>) -> SimulatorBuilder<'a, T, F> {
+        SimulatorBuilder {
+            builder: seq::Simulator::builder(population),
+            threads: default_threads(),
+        }
+    }
+
+    fn step(&mut self) -> StepResult {
+        let threads = self.threads;
+        self.sim.step_with(|parents, variations| {
+            let pairs: Vec<_> = parents.iter().zip(variations).collect();
+            parallel_map(&pairs, threads, |&((a, b), variation)| {
+                    variation.breed(a, b).into_iter().map(Evaluated::new).collect::<Vec<_>>()
+                })
+                .into_iter()
+                .flatten()
+                .collect()
+        })
+    }
+
+    #[allow(deprecated)]
+    fn checked_step(&mut self) -> StepResult {
+        if self.sim.has_error() {
+            panic!("Attemped to step a Simulator after an error!")
+        } else {
+            self.step()
+        }
+    }
+
+    #[allow(deprecated)]
+    fn run(&mut self) -> RunResult {
+        // Loop until Failure or Done.
+        loop {
+            match self.step() {
+                StepResult::Success => {}
+                StepResult::Failure => return RunResult::Failure,
+                StepResult::Done => return RunResult::Done,
+            }
+        }
+    }
+
+    fn get(&self) -> SimResult<'_, T> {
+        self.sim.get()
+    }
+
+    fn iterations(&self) -> u64 {
+        self.sim.iterations()
+    }
+
+    fn time(&self) -> Option<NanoSecond> {
+        self.sim.time()
+    }
+
+    fn population(&self) -> Vec<T> {
+        self.sim.population()
+    }
+
+    fn history(&self) -> &[Stats<F>] {
+        self.sim.history()
+    }
+
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.sim.stop_reason()
+    }
+}
+
+impl<'a, T, F> Simulator<'a, T, F>
+    where T: Phenotype<F> + Send + Sync,
+          F: Fitness + Send
+{
+    /// Create a builder for a `Simulator` that owns `population`, instead of borrowing it.
+    ///
+    /// Use `into_population` to get the final population back.
+    pub fn owned_builder(population: Vec<T>) -> SimulatorBuilder<'a, T, F> {
+        SimulatorBuilder {
+            builder: seq::Simulator::owned_builder(population),
+            threads: default_threads(),
+        }
+    }
+
+    /// Consume the `Simulator` and return its current population.
+    pub fn into_population(self) -> Vec<T> {
+        self.sim.into_population()
+    }
+
+    /// Get the number of threads this `Simulator` uses.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Capture the state of the simulation in a `Checkpoint`, from which it can be resumed
+    /// with `SimulatorBuilder::resume`.
+    pub fn checkpoint(&self) -> Checkpoint<T, F> {
+        self.sim.checkpoint()
+    }
+}
+
+/// The number of threads to use if none is set: one per available CPU.
+fn default_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Apply `f` to every element of `items`, spreading the work over at most
+/// `threads` threads. The order of the results matches the order of `items`.
+///
+/// If `f` panics on any thread, the panic is propagated to the caller.
+fn parallel_map<A, B, G>(items: &[A], threads: usize, f: G) -> Vec<B>
+    where A: Sync,
+          B: Send,
+          G: Fn(&A) -> B + Sync
+{
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let chunk_size = items.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<B>>()))
+            .collect();
+        let mut result = Vec::with_capacity(items.len());
+        for handle in handles {
+            match handle.join() {
+                Ok(mut part) => result.append(&mut part),
+                Err(e) => panic::resume_unwind(e),
+            }
+        }
+        result
+    })
+}
+
+/// A `Builder` for the `Simulator` type.
+#[derive(Debug)]
+pub struct SimulatorBuilder<'a, T, F>
+    where T: 'a + Phenotype<F> + Send + Sync,
+          F: Fitness + Send
+{
+    builder: seq::SimulatorBuilder<'a, T, F>,
+    threads: usize,
+}
+
+impl<'a, T, F> SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F> + Send + Sync,
+          F: Fitness + Send
+{
+    /// Set the selector of the resulting `Simulator`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_selector(mut self, sel: Box<dyn Selector<T, F>>) -> Self {
+        self.builder = self.builder.set_selector(sel);
+        self
+    }
+
+    /// Set the replacement strategy of the resulting `Simulator`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_replacement(mut self, replacement: Box<dyn Replacement<T, F>>) -> Self {
+        self.builder = self.builder.set_replacement(replacement);
+        self
+    }
+
+    /// Set the maximum number of iterations of the resulting `Simulator`.
+    ///
+    /// The `Simulator` will stop running after this number of iterations.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_max_iters(mut self, i: u64) -> Self {
+        self.builder = self.builder.set_max_iters(i);
+        self
+    }
+
+    /// Set early stopping. If for `n_iters` iterations, the change in the highest fitness
+    /// is smaller than `delta`, the simulator will stop running.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_early_stop(mut self, delta: F, n_iters: u64) -> Self {
+        self.builder = self.builder.set_early_stop(delta, n_iters);
+        self
+    }
+
+    /// Set a stopping criterion, which replaces the default maximum number of iterations.
+    /// See `seq::SimulatorBuilder::set_stop_criterion` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_stop_criterion(mut self, criterion: Box<dyn StopCriterion<T, F>>) -> Self {
+        self.builder = self.builder.set_stop_criterion(criterion);
+        self
+    }
+
+    /// Set elitism. The `n` best phenotypes of the population are guaranteed to survive
+    /// every step.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_elitism(mut self, n: usize) -> Self {
+        self.builder = self.builder.set_elitism(n);
+        self
+    }
+
+    /// Add an observer, which is notified of the progress of the resulting `Simulator`.
+    /// Observers are always called on the thread that steps the `Simulator`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn add_observer(mut self, observer: Box<dyn Observer<T, F>>) -> Self {
+        self.builder = self.builder.add_observer(observer);
+        self
+    }
+
+    /// Set the random number generator of the resulting `Simulator`.
+    ///
+    /// All randomness used by the `Simulator` and its `Selector` is drawn from
+    /// this generator. By default, a generator seeded from the thread-local
+    /// random number generator is used.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_rng(mut self, rng: SimRng) -> Self {
+        self.builder = self.builder.set_rng(rng);
+        self
+    }
+
+    /// Seed the random number generator of the resulting `Simulator`.
+    ///
+    /// The built-in genomes draw their random numbers from this generator, so runs
+    /// with them are reproducible. A `Phenotype` that uses `rand::thread_rng` is not.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.builder = self.builder.set_seed(seed);
+        self
+    }
+
+    /// Set the probability that a pair of parents creates its children by crossover.
+    /// See `seq::SimulatorBuilder::set_crossover_rate` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover_rate(mut self, rate: f64) -> Self {
+        self.builder = self.builder.set_crossover_rate(rate);
+        self
+    }
+
+    /// Set the probability that a child is mutated after crossover.
+    /// See `seq::SimulatorBuilder::set_mutation_rate` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.builder = self.builder.set_mutation_rate(rate);
+        self
+    }
+
+    /// Set an initializer, which creates `size` individuals that are added to
+    /// the initial population. See `seq::SimulatorBuilder::set_initializer` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_initializer(mut self, initializer: Box<dyn Initializer<T>>, size: usize) -> Self {
+        self.builder = self.builder.set_initializer(initializer, size);
+        self
+    }
+
+    /// Resume the simulation captured in `checkpoint`.
+    /// See `seq::SimulatorBuilder::resume` for details.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn resume(mut self, checkpoint: Checkpoint<T, F>) -> Self {
+        self.builder = self.builder.resume(checkpoint);
+        self
+    }
+
+    /// Set the number of threads used by the resulting `Simulator`.
+    ///
+    /// By default, one thread per available CPU is used. A value of `0` is
+    /// treated as `1`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+}
+
+impl<'a, T, F> Builder<Simulator<'a, T, F>> for SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F> + Send + Sync,
+          F: Fitness + Send
+{
+    fn build(self) -> Simulator<'a, T, F> {
+        let threads = self.threads;
+        Simulator {
+            sim: self.builder.build_with(|population| {
+                parallel_map(population, threads, |x| x.fitness())
+            }),
+            threads,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::*;
+    use ::sim::select::*;
+    use test::Test;
+    use test::MyFitness;
+    use super::parallel_map;
+    use std::cell::Cell;
+
+    thread_local!(static EVALUATIONS: Cell<usize> = const { Cell::new(0) });
+
+    /// A `Phenotype` that counts how often its fitness is evaluated on the current thread.
+    #[derive(Clone, Copy)]
+    struct Counted {
+        f: i64,
+    }
+
+    impl Phenotype<MyFitness> for Counted {
+        fn fitness(&self) -> MyFitness {
+            EVALUATIONS.with(|e| e.set(e.get() + 1));
+            MyFitness { f: self.f }
+        }
+
+        fn crossover(&self, other: &Counted) -> Counted {
+            Counted { f: (self.f + other.f) / 2 }
+        }
+
+        fn mutate(&self) -> Counted {
+            *self
+        }
+    }
+
+    #[test]
+    fn test_parallel_map_order() {
+        let items: Vec<u64> = (0..103).collect();
+        for threads in 1..8 {
+            let mapped = parallel_map(&items, threads, |x| x * 2);
+            assert_eq!(mapped, items.iter().map(|x| x * 2).collect::<Vec<u64>>());
+        }
+    }
+
+    #[test]
+    fn test_parallel_map_empty() {
+        let items: Vec<u64> = Vec::new();
+        assert!(parallel_map(&items, 4, |x| x * 2).is_empty());
+    }
+
+    #[test]
+    fn test_max_iters() {
+        let selector = MaximizeSelector::new(2);
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = par::Simulator::builder(&mut population)
+            .set_selector(Box::new(selector))
+            .set_max_iters(2)
+            .set_threads(4)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert_eq!(s.iterations(), 2);
+        assert_eq!(s.population().len(), 100);
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        let selector = MaximizeSelector::new(2);
+        let mut population: Vec<Test> = (0..100).map(|_| Test { f: 0 }).collect();
+        let mut s = par::Simulator::builder(&mut population)
+            .set_selector(Box::new(selector))
+            .set_early_stop(MyFitness { f: 10 }, 5)
+            .set_max_iters(10)
+            .set_threads(3)
+            .build();
+        s.run();
+        assert!(s.iterations() <= 5);
+    }
+
+    #[test]
+    fn test_selector_error_propagate() {
+        let selector = MaximizeSelector::new(0);
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = par::Simulator::builder(&mut population)
+            .set_selector(Box::new(selector))
+            .build();
+        assert_eq!(s.run(), RunResult::Failure);
+        assert!(s.get().is_err());
+    }
+
+    #[test]
+    fn test_same_seed_same_result() {
+        let run = |threads| {
+            let mut population: Vec<Test> = (-50..50).map(|i| Test { f: i * 3 }).collect();
+            let mut s = par::Simulator::builder(&mut population)
+                .set_selector(Box::new(StochasticSelector::new(10)))
+                .set_max_iters(20)
+                .set_threads(threads)
+                .set_seed(42)
+                .build();
+            s.run();
+            let population: Vec<i64> = s.population().iter().map(|x| x.f).collect();
+            (s.get().unwrap().f, s.iterations(), population)
+        };
+        assert_eq!(run(1), run(4));
+    }
+
+    #[test]
+    fn test_children_evaluated_in_parallel() {
+        let mut population: Vec<Counted> = (0..100).map(|i| Counted { f: i }).collect();
+        let mut s = par::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_max_iters(5)
+            .set_threads(4)
+            .build();
+        let before = EVALUATIONS.with(|e| e.get());
+        assert_eq!(s.run(), RunResult::Done);
+        // All children are evaluated on the worker threads.
+        assert_eq!(EVALUATIONS.with(|e| e.get()), before);
+    }
+
+    #[test]
+    fn test_threads_zero() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let s = par::Simulator::builder(&mut population)
+            .set_threads(0)
+            .build();
+        assert_eq!(s.threads(), 1);
+    }
+}
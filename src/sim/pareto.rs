@@ -0,0 +1,173 @@
+// file: pareto.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the building blocks of multi-objective optimisation:
+//! Pareto dominance, non-dominated sorting and crowding distance.
+//!
+//! All functions work on `MultiFitness` values, of which every objective is maximised.
+
+use pheno::MultiFitness;
+use std::cmp::Ordering;
+use std::f64;
+
+/// Returns whether `a` Pareto-dominates `b`.
+///
+/// `a` dominates `b` if `a` is at least as good as `b` in every objective,
+/// and strictly better in at least one objective.
+pub fn dominates<F: MultiFitness>(a: &F, b: &F) -> bool {
+    let mut strictly_better = false;
+    for i in 0..a.num_objectives() {
+        let (x, y) = (a.objective(i), b.objective(i));
+        if x < y {
+            return false;
+        }
+        if x > y {
+            strictly_better = true;
+        }
+    }
+    strictly_better
+}
+
+/// Sort `fitnesses` into non-dominated fronts.
+///
+/// The first front contains the indices of all values that are not dominated
+/// by any other value. The second front contains the values that are only dominated
+/// by values of the first front, and so on. Every index appears in exactly one front.
+pub fn non_dominated_sort<F: MultiFitness>(fitnesses: &[&F]) -> Vec<Vec<usize>> {
+    let n = fitnesses.len();
+    // For every value, the values it dominates and the number of values dominating it.
+    let mut dominated: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut domination_count: Vec<usize> = vec![0; n];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if dominates(fitnesses[i], fitnesses[j]) {
+                dominated[i].push(j);
+                domination_count[j] += 1;
+            } else if dominates(fitnesses[j], fitnesses[i]) {
+                dominated[j].push(i);
+                domination_count[i] += 1;
+            }
+        }
+    }
+
+    let mut fronts: Vec<Vec<usize>> = Vec::new();
+    let mut current: Vec<usize> = (0..n).filter(|&i| domination_count[i] == 0).collect();
+    while !current.is_empty() {
+        let mut next = Vec::new();
+        for &i in &current {
+            for &j in &dominated[i] {
+                domination_count[j] -= 1;
+                if domination_count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        next.sort_unstable();
+        fronts.push(current);
+        current = next;
+    }
+    fronts
+}
+
+/// Compute the crowding distance of every member of `front`, which contains indices
+/// into `fitnesses`. The result is in the same order as `front`.
+///
+/// The crowding distance estimates how densely the objective space around a value
+/// is populated: values at the boundaries of the front have an infinite distance,
+/// other values have the sum of the normalised distances between their neighbours.
+pub fn crowding_distance<F: MultiFitness>(fitnesses: &[&F], front: &[usize]) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    if front.is_empty() {
+        return distance;
+    }
+    let num_objectives = fitnesses[front[0]].num_objectives();
+    let mut order: Vec<usize> = (0..front.len()).collect();
+    for m in 0..num_objectives {
+        let value = |i: usize| fitnesses[front[i]].objective(m);
+        order.sort_by(|&a, &b| value(a).partial_cmp(&value(b)).unwrap_or(Ordering::Equal));
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distance[first] = f64::INFINITY;
+        distance[last] = f64::INFINITY;
+        let range = value(last) - value(first);
+        if !range.is_finite() || range <= 0.0 {
+            continue;
+        }
+        for k in 1..(order.len() - 1) {
+            distance[order[k]] += (value(order[k + 1]) - value(order[k - 1])) / range;
+        }
+    }
+    distance
+}
+
+/// Compare two individuals using the crowded-comparison operator of NSGA-II.
+///
+/// An individual with a lower `rank` (front index) is better. Within the same
+/// front, the individual with the larger crowding `distance` is better.
+/// Returns `Ordering::Greater` if the first individual is better.
+pub fn crowded_cmp(a: (usize, f64), b: (usize, f64)) -> Ordering {
+    b.0
+        .cmp(&a.0)
+        .then_with(|| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sim::types::Objectives;
+    use std::cmp::Ordering;
+
+    fn objectives(values: &[(f64, f64)]) -> Vec<Objectives> {
+        values.iter().map(|&(a, b)| Objectives(vec![a, b])).collect()
+    }
+
+    #[test]
+    fn test_dominates() {
+        let a = Objectives(vec![1.0, 2.0]);
+        let b = Objectives(vec![1.0, 1.0]);
+        let c = Objectives(vec![2.0, 0.0]);
+        assert!(dominates(&a, &b));
+        assert!(!dominates(&b, &a));
+        assert!(!dominates(&a, &c));
+        assert!(!dominates(&c, &a));
+        assert!(!dominates(&a, &a));
+    }
+
+    #[test]
+    fn test_non_dominated_sort() {
+        let values = objectives(&[(1.0, 5.0), (5.0, 1.0), (0.0, 0.0), (3.0, 3.0), (2.0, 2.0)]);
+        let refs: Vec<&Objectives> = values.iter().collect();
+        let fronts = non_dominated_sort(&refs);
+        assert_eq!(fronts, vec![vec![0, 1, 3], vec![4], vec![2]]);
+    }
+
+    #[test]
+    fn test_crowding_distance() {
+        let values = objectives(&[(0.0, 4.0), (1.0, 3.0), (3.0, 1.0), (4.0, 0.0)]);
+        let refs: Vec<&Objectives> = values.iter().collect();
+        let distance = crowding_distance(&refs, &[0, 1, 2, 3]);
+        assert!(distance[0].is_infinite());
+        assert!(distance[3].is_infinite());
+        assert!((distance[1] - 1.5).abs() < 1e-9);
+        assert!((distance[2] - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_crowded_cmp() {
+        assert_eq!(crowded_cmp((0, 0.0), (1, 10.0)), Ordering::Greater);
+        assert_eq!(crowded_cmp((1, 2.0), (1, 1.0)), Ordering::Greater);
+        assert_eq!(crowded_cmp((2, 1.0), (1, 1.0)), Ordering::Less);
+    }
+}
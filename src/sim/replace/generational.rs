@@ -0,0 +1,76 @@
+// file: generational.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::Rng;
+use super::*;
+
+/// Replaces the entire population with children.
+///
+/// If there are more children than individuals in the population,
+/// a random subset of the children survives.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GenerationalReplacement;
+
+impl GenerationalReplacement {
+    /// Create and return a generational replacement strategy.
+    ///
+    /// There should be at least as many children as individuals in the population.
+    pub fn new() -> GenerationalReplacement {
+        GenerationalReplacement
+    }
+}
+
+impl<T, F> Replacement<T, F> for GenerationalReplacement
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn replace(&self,
+               population: Vec<Evaluated<T, F>>,
+               mut children: Vec<Evaluated<T, F>>,
+               rng: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError> {
+        check_enough(&population, &children)?;
+        if children.len() > population.len() {
+            rng.shuffle(&mut children);
+            children.truncate(population.len());
+        }
+        Ok(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::replace::*;
+    use ::sim::replace::tests::{evaluated, values};
+    use ::sim::SimRng;
+
+    #[test]
+    fn test_children_replace_everyone() {
+        let result = GenerationalReplacement::new()
+            .replace(evaluated(&[1, 2, 3]), evaluated(&[4, 5, 6, 7, 8]), &mut SimRng::new(0))
+            .unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(values(&result).iter().all(|&f| f >= 4));
+    }
+
+    #[test]
+    fn test_not_enough_children() {
+        let result = GenerationalReplacement::new()
+            .replace(evaluated(&[1, 2, 3]), evaluated(&[4, 5]), &mut SimRng::new(0));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,119 @@
+// file: mod.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The replacement module provides a trait that can be implemented
+//! to decide which individuals survive a step of a `Simulation`. This module
+//! also provides a couple of useful replacement strategies.
+//!
+//! A replacement strategy receives the current population and the children created
+//! in a step, and returns the next population. All strategies in this module keep
+//! the size of the population constant.
+
+mod stochastic;
+mod generational;
+mod worst;
+mod mu_lambda;
+mod random;
+
+use pheno::{Fitness, Phenotype};
+use sim::{Evaluated, SimError, SimRng};
+use std::fmt::Debug;
+
+pub use self::stochastic::StochasticReplacement;
+pub use self::generational::GenerationalReplacement;
+pub use self::worst::WorstReplacement;
+pub use self::mu_lambda::{MuCommaLambdaReplacement, MuPlusLambdaReplacement};
+pub use self::random::RandomReplacement;
+
+/// A `Replacement` chooses the survivors of a step of a `Simulation`.
+pub trait Replacement<T, F>: Debug + Send
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Combine the current `population` and the newly created `children` into
+    /// the population for the next step.
+    ///
+    /// If the simulator uses elitism, the elite are not part of `population`:
+    /// they always survive and are added back after replacement.
+    ///
+    /// Any randomness should be drawn from `rng`, so that simulations with
+    /// the same seed produce the same results.
+    ///
+    /// If the strategy cannot be applied, for example because there are not enough
+    /// children, this function returns an `Err(SimError)`, indicating the error.
+    fn replace(&self,
+               population: Vec<Evaluated<T, F>>,
+               children: Vec<Evaluated<T, F>>,
+               rng: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError>;
+}
+
+/// Sort `individuals` from the highest to the lowest fitness.
+fn sort_best_first<T, F>(individuals: &mut [Evaluated<T, F>])
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    individuals.sort_by(|x, y| y.fitness().cmp(x.fitness()));
+}
+
+/// Check that there are no more `children` than individuals in `population`
+/// that they can replace.
+fn check_fits<T, F>(population: &[Evaluated<T, F>],
+                    children: &[Evaluated<T, F>])
+                    -> Result<(), SimError>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    if children.len() > population.len() {
+        return Err(SimError::TooManyChildren {
+            children: children.len(),
+            available: population.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Check that there are at least as many `children` as individuals in `population`.
+fn check_enough<T, F>(population: &[Evaluated<T, F>],
+                      children: &[Evaluated<T, F>])
+                      -> Result<(), SimError>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    if children.len() < population.len() {
+        return Err(SimError::NotEnoughChildren {
+            children: children.len(),
+            required: population.len(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sim::Evaluated;
+    use test::{MyFitness, Test};
+
+    pub fn evaluated(values: &[i64]) -> Vec<Evaluated<Test, MyFitness>> {
+        values.iter().map(|&f| Evaluated::new(Test { f })).collect()
+    }
+
+    pub fn values(population: &[Evaluated<Test, MyFitness>]) -> Vec<i64> {
+        let mut values: Vec<i64> = population.iter().map(|x| x.pheno().f).collect();
+        values.sort_unstable();
+        values
+    }
+}
@@ -0,0 +1,111 @@
+// file: mu_lambda.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use super::*;
+
+/// The *(mu+lambda)* strategy: the best individuals of the population and
+/// the children together survive.
+///
+/// The best individual never gets lost with this strategy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MuPlusLambdaReplacement;
+
+impl MuPlusLambdaReplacement {
+    /// Create and return a (mu+lambda) replacement strategy.
+    ///
+    /// Any number of children can be used.
+    pub fn new() -> MuPlusLambdaReplacement {
+        MuPlusLambdaReplacement
+    }
+}
+
+impl<T, F> Replacement<T, F> for MuPlusLambdaReplacement
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn replace(&self,
+               mut population: Vec<Evaluated<T, F>>,
+               mut children: Vec<Evaluated<T, F>>,
+               _: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError> {
+        let size = population.len();
+        population.append(&mut children);
+        sort_best_first(&mut population);
+        population.truncate(size);
+        Ok(population)
+    }
+}
+
+/// The *(mu,lambda)* strategy: only the best children survive,
+/// the current population is discarded entirely.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MuCommaLambdaReplacement;
+
+impl MuCommaLambdaReplacement {
+    /// Create and return a (mu,lambda) replacement strategy.
+    ///
+    /// There should be at least as many children as individuals in the population.
+    pub fn new() -> MuCommaLambdaReplacement {
+        MuCommaLambdaReplacement
+    }
+}
+
+impl<T, F> Replacement<T, F> for MuCommaLambdaReplacement
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn replace(&self,
+               population: Vec<Evaluated<T, F>>,
+               mut children: Vec<Evaluated<T, F>>,
+               _: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError> {
+        check_enough(&population, &children)?;
+        sort_best_first(&mut children);
+        children.truncate(population.len());
+        Ok(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::replace::*;
+    use ::sim::replace::tests::{evaluated, values};
+    use ::sim::SimRng;
+
+    #[test]
+    fn test_plus() {
+        let result = MuPlusLambdaReplacement::new()
+            .replace(evaluated(&[1, 5, 3]), evaluated(&[4, 2]), &mut SimRng::new(0))
+            .unwrap();
+        assert_eq!(values(&result), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_comma() {
+        let result = MuCommaLambdaReplacement::new()
+            .replace(evaluated(&[7, 8]), evaluated(&[1, 5, 3, 4]), &mut SimRng::new(0))
+            .unwrap();
+        assert_eq!(values(&result), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_comma_not_enough_children() {
+        let result = MuCommaLambdaReplacement::new()
+            .replace(evaluated(&[7, 8]), evaluated(&[1]), &mut SimRng::new(0));
+        assert!(result.is_err());
+    }
+}
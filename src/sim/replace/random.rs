@@ -0,0 +1,75 @@
+// file: random.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::Rng;
+use super::*;
+
+/// Replaces individuals chosen uniformly at random by the children.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomReplacement;
+
+impl RandomReplacement {
+    /// Create and return a random replacement strategy.
+    ///
+    /// There should not be more children than individuals in the population.
+    pub fn new() -> RandomReplacement {
+        RandomReplacement
+    }
+}
+
+impl<T, F> Replacement<T, F> for RandomReplacement
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn replace(&self,
+               mut population: Vec<Evaluated<T, F>>,
+               mut children: Vec<Evaluated<T, F>>,
+               rng: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError> {
+        check_fits(&population, &children)?;
+        rng.shuffle(&mut population);
+        let survivors = population.len() - children.len();
+        population.truncate(survivors);
+        population.append(&mut children);
+        Ok(population)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::replace::*;
+    use ::sim::replace::tests::{evaluated, values};
+    use ::sim::SimRng;
+
+    #[test]
+    fn test_size() {
+        let population = evaluated(&(0..100).collect::<Vec<i64>>());
+        let children = evaluated(&[200; 30]);
+        let result = RandomReplacement::new()
+            .replace(population, children, &mut SimRng::new(2))
+            .unwrap();
+        assert_eq!(result.len(), 100);
+        assert_eq!(values(&result)[70..], [200; 30]);
+    }
+
+    #[test]
+    fn test_too_many_children() {
+        let result = RandomReplacement::new()
+            .replace(evaluated(&[1, 2]), evaluated(&[4, 5, 6]), &mut SimRng::new(0));
+        assert!(result.is_err());
+    }
+}
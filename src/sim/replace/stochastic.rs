@@ -0,0 +1,102 @@
+// file: stochastic.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use rand::Rng;
+use super::*;
+
+/// Kills off individuals at evenly spaced positions in the population,
+/// starting at a random position, to make room for the children.
+///
+/// This is the default replacement strategy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StochasticReplacement;
+
+impl StochasticReplacement {
+    /// Create and return a stochastic replacement strategy.
+    ///
+    /// There should not be more children than individuals in the population.
+    pub fn new() -> StochasticReplacement {
+        StochasticReplacement
+    }
+}
+
+impl<T, F> Replacement<T, F> for StochasticReplacement
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn replace(&self,
+               mut population: Vec<Evaluated<T, F>>,
+               mut children: Vec<Evaluated<T, F>>,
+               rng: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError> {
+        check_fits(&population, &children)?;
+        if !children.is_empty() {
+            let ratio = population.len() / children.len();
+            let mut i = rng.gen_range::<usize>(0, population.len());
+            let mut selected = 0;
+            while selected < children.len() {
+                population.remove(i);
+                if population.is_empty() {
+                    break;
+                }
+                i += ratio - 1;
+                i %= population.len();
+
+                selected += 1;
+            }
+        }
+        population.append(&mut children);
+        Ok(population)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::replace::*;
+    use ::sim::replace::tests::{evaluated, values};
+    use ::sim::{SimError, SimRng};
+
+    #[test]
+    fn test_size() {
+        let population = evaluated(&(0..100).collect::<Vec<i64>>());
+        let children = evaluated(&[200; 10]);
+        let result = StochasticReplacement::new()
+            .replace(population, children, &mut SimRng::new(0))
+            .unwrap();
+        assert_eq!(result.len(), 100);
+        assert_eq!(values(&result)[90..], [200; 10]);
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let result = StochasticReplacement::new()
+            .replace(evaluated(&[1, 2, 3]), evaluated(&[4, 5, 6]), &mut SimRng::new(0))
+            .unwrap();
+        assert_eq!(values(&result), vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn test_too_many_children() {
+        let result = StochasticReplacement::new()
+            .replace(evaluated(&[1, 2]), evaluated(&[4, 5, 6]), &mut SimRng::new(0));
+        assert_eq!(result.err(),
+                   Some(SimError::TooManyChildren {
+                       children: 3,
+                       available: 2,
+                   }));
+    }
+}
@@ -0,0 +1,73 @@
+// file: worst.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use super::*;
+
+/// Replaces the individuals with the lowest fitness by the children.
+///
+/// This is also known as *steady-state* replacement.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WorstReplacement;
+
+impl WorstReplacement {
+    /// Create and return a replace-worst strategy.
+    ///
+    /// There should not be more children than individuals in the population.
+    pub fn new() -> WorstReplacement {
+        WorstReplacement
+    }
+}
+
+impl<T, F> Replacement<T, F> for WorstReplacement
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn replace(&self,
+               mut population: Vec<Evaluated<T, F>>,
+               mut children: Vec<Evaluated<T, F>>,
+               _: &mut SimRng)
+               -> Result<Vec<Evaluated<T, F>>, SimError> {
+        check_fits(&population, &children)?;
+        sort_best_first(&mut population);
+        let survivors = population.len() - children.len();
+        population.truncate(survivors);
+        population.append(&mut children);
+        Ok(population)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::replace::*;
+    use ::sim::replace::tests::{evaluated, values};
+    use ::sim::SimRng;
+
+    #[test]
+    fn test_worst_replaced() {
+        let result = WorstReplacement::new()
+            .replace(evaluated(&[5, 1, 4, 2, 3]), evaluated(&[0, 0]), &mut SimRng::new(0))
+            .unwrap();
+        assert_eq!(values(&result), vec![0, 0, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_too_many_children() {
+        let result = WorstReplacement::new()
+            .replace(evaluated(&[1]), evaluated(&[2, 3]), &mut SimRng::new(0));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,185 @@
+// file: rng.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rand::{Rand, Rng, SeedableRng};
+use std::cell::Cell;
+
+thread_local!(static LOCAL: Cell<Option<SimRng>> = const { Cell::new(None) });
+
+/// The random number generator used by simulators and selectors.
+///
+/// This is a small and fast Xorshift generator. It is *not* suitable for
+/// cryptographic purposes. Two generators created from the same seed
+/// produce the same sequence of numbers, which makes simulations reproducible.
+///
+/// A `SimRng` can be created from a seed with `SimRng::new`, or from any other
+/// random number generator with `rng.gen::<SimRng>()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SimRng {
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32,
+}
+
+impl SimRng {
+    /// Create a new generator from a `seed`.
+    ///
+    /// Every seed, including zero, results in a valid generator.
+    pub fn new(seed: u64) -> SimRng {
+        // Expand the seed with SplitMix64, so that similar seeds
+        // still result in very different states.
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        let (a, b) = (next(), next());
+        SimRng::from_state(a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32)
+    }
+
+    /// Create a new generator seeded from the thread-local random number generator.
+    pub fn from_thread_rng() -> SimRng {
+        ::rand::thread_rng().gen()
+    }
+
+    /// Create a generator from its raw state. An all-zero state is replaced by a
+    /// fixed non-zero state, because Xorshift would only produce zeroes.
+    fn from_state(x: u32, y: u32, z: u32, w: u32) -> SimRng {
+        if x == 0 && y == 0 && z == 0 && w == 0 {
+            SimRng::new(0x5EED)
+        } else {
+            SimRng { x, y, z, w }
+        }
+    }
+}
+
+impl Rng for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        let t = self.x ^ (self.x << 11);
+        self.x = self.y;
+        self.y = self.z;
+        self.z = self.w;
+        self.w = self.w ^ (self.w >> 19) ^ (t ^ (t >> 8));
+        self.w
+    }
+}
+
+impl SeedableRng<u64> for SimRng {
+    fn reseed(&mut self, seed: u64) {
+        *self = SimRng::new(seed);
+    }
+
+    fn from_seed(seed: u64) -> SimRng {
+        SimRng::new(seed)
+    }
+}
+
+impl Rand for SimRng {
+    fn rand<R: Rng>(other: &mut R) -> SimRng {
+        SimRng::from_state(other.next_u32(),
+                           other.next_u32(),
+                           other.next_u32(),
+                           other.next_u32())
+    }
+}
+
+/// The random number generator of the current thread that the built-in genomes use
+/// for their operators.
+///
+/// Simulators install a generator derived from their own `SimRng` with `LocalRng::replace`
+/// before creating children, so that simulations with the built-in genomes are reproducible.
+/// Outside of a simulation, the generator is seeded from the thread-local random number
+/// generator. A `LocalRng` writes its state back when it is dropped, so only one of them
+/// should be alive at a time.
+#[derive(Debug)]
+pub(crate) struct LocalRng {
+    rng: SimRng,
+}
+
+impl LocalRng {
+    /// Take the generator of the current thread.
+    pub(crate) fn get() -> LocalRng {
+        let rng = LOCAL.with(|cell| cell.get()).unwrap_or_else(SimRng::from_thread_rng);
+        LocalRng { rng }
+    }
+
+    /// Install `rng` as the generator of the current thread, and return the previous one.
+    pub(crate) fn replace(rng: Option<SimRng>) -> Option<SimRng> {
+        LOCAL.with(|cell| cell.replace(rng))
+    }
+}
+
+impl Rng for LocalRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+}
+
+impl Drop for LocalRng {
+    fn drop(&mut self) {
+        LOCAL.with(|cell| cell.set(Some(self.rng)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalRng, SimRng};
+    use rand::Rng;
+
+    #[test]
+    fn test_local_rng() {
+        let draw = || {
+            let previous = LocalRng::replace(Some(SimRng::new(3)));
+            let xs: Vec<u32> = (0..5).map(|_| LocalRng::get().next_u32()).collect();
+            LocalRng::replace(previous);
+            xs
+        };
+        let xs = draw();
+        assert_eq!(xs, draw());
+        // Every `LocalRng` continues where the previous one stopped.
+        let mut expected = SimRng::new(3);
+        assert_eq!(xs, (0..5).map(|_| expected.next_u32()).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = SimRng::new(42);
+        let mut b = SimRng::new(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_different_seed_different_sequence() {
+        let mut a = SimRng::new(1);
+        let mut b = SimRng::new(2);
+        let xs: Vec<u32> = (0..10).map(|_| a.next_u32()).collect();
+        let ys: Vec<u32> = (0..10).map(|_| b.next_u32()).collect();
+        assert!(xs != ys);
+    }
+
+    #[test]
+    fn test_zero_seed() {
+        let mut rng = SimRng::new(0);
+        assert!((0..10).any(|_| rng.next_u32() != 0));
+    }
+}
@@ -39,18 +39,29 @@ impl<T, F> Selector<T, F> for MaximizeSelector
     where T: Phenotype<F>,
           F: Fitness
 {
-    fn select(&self, population: &[T]) -> Result<Parents<T>, String> {
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError> {
+        self.select_evaluated(population, rng).map(phenotypes)
+    }
+
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        _: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
         if self.count == 0 || self.count % 2 != 0 || self.count * 2 >= population.len() {
-            return Err(format!("Invalid parameter `count`: {}. Should be larger than zero, a \
-                                multiple of two and less than half the population size.",
-                               self.count));
+            return Err(SimError::invalid_parameter("count",
+                                                   self.count,
+                                                   "Should be larger than zero, a multiple of \
+                                                    two and less than half the population size."));
         }
 
-        let mut cloned = population.to_vec();
-        cloned.sort_by(|x, y| y.fitness().cmp(&x.fitness()));
-        let sorted: Vec<&T> = cloned.iter().take(self.count).collect();
+        let mut sorted: Vec<&Evaluated<T, F>> = population.iter().collect();
+        sorted.sort_by(|x, y| y.fitness().cmp(x.fitness()));
+        sorted.truncate(self.count);
         let mut index = 0;
-        let mut result: Parents<T> = Vec::new();
+        let mut result: EvaluatedParents<T, F> = Vec::new();
         while index < sorted.len() {
             result.push((sorted[index].clone(), sorted[index + 1].clone()));
             index += 2;
@@ -62,54 +73,61 @@ impl<T, F> Selector<T, F> for MaximizeSelector
 #[cfg(test)]
 mod tests {
     use ::sim::select::*;
+    use ::sim::{Evaluated, SimRng};
     use ::pheno::*;
-    use test::Test;
+    use test::{MyFitness, Test};
 
     #[test]
     fn test_count_zero() {
         let selector = MaximizeSelector::new(0);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_count_odd() {
         let selector = MaximizeSelector::new(5);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_count_too_large() {
         let selector = MaximizeSelector::new(100);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_result_size() {
         let selector = MaximizeSelector::new(20);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert_eq!(20, selector.select(&population).unwrap().len() * 2);
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert_eq!(20, selector.select(&population, &mut SimRng::new(0)).unwrap().len() * 2);
     }
 
     #[test]
     fn test_result_ok() {
         let selector = MaximizeSelector::new(20);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
         // The greatest fitness should be 99.
-        assert!(selector.select(&population).unwrap()[0].0.fitness().f == 99);
+        assert!(selector.select(&population, &mut SimRng::new(0)).unwrap()[0].0.fitness().f == 99);
     }
 
     #[test]
     fn test_contains_best() {
         let selector = MaximizeSelector::new(2);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        let parents = selector.select(&population).unwrap()[0];
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        let parents = selector.select(&population, &mut SimRng::new(0)).unwrap()[0];
         assert!(parents.0.fitness() ==
-                population.iter()
-            .max_by_key(|x| x.fitness())
-            .unwrap()
-            .fitness());
+                *population.iter()
+            .map(|x| x.fitness())
+            .max()
+            .unwrap());
     }
 }
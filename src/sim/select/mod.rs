@@ -24,27 +24,70 @@
 mod max;
 mod tournament;
 mod stochastic;
+mod roulette;
+mod sus;
+mod rank;
 
 use pheno::{Fitness, Phenotype};
+use sim::{Evaluated, SimError, SimRng};
 use std::fmt::Debug;
 
 pub use self::max::MaximizeSelector;
 pub use self::tournament::TournamentSelector;
 pub use self::stochastic::StochasticSelector;
+pub use self::roulette::RouletteSelector;
+pub use self::sus::SusSelector;
+pub use self::rank::RankSelector;
 
 /// `Parents` come in a `Vec` of two `T`'s.
 pub type Parents<T> = Vec<(T, T)>;
 
+/// `EvaluatedParents` are `Parents` that keep their cached fitness values.
+pub type EvaluatedParents<T, F> = Vec<(Evaluated<T, F>, Evaluated<T, F>)>;
+
 /// A `Selector` can select `Parents` for a new iteration of a `Simulation`.
-pub trait Selector<T, F>: Debug
+pub trait Selector<T, F>: Debug + Send
     where T: Phenotype<F>,
           F: Fitness
 {
     /// Select elements from a `population` for breeding.
     ///
+    /// Every individual in `population` comes with its cached fitness value,
+    /// which selectors should use instead of calling `Phenotype::fitness`.
+    ///
+    /// Any randomness should be drawn from `rng`, so that simulations with
+    /// the same seed produce the same results.
+    ///
     /// If invalid parameters are supplied or the algorithm fails, this function returns an
-    /// `Err(String)`, containing a message indicating the error.
+    /// `Err(SimError)`, indicating the error.
     ///
     /// Otherwise it contains a vector of parent pairs wrapped in `Ok`.
-    fn select(&self, population: &[T]) -> Result<Parents<T>, String>;
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError>;
+
+    /// Select elements from a `population` like `select`, but keep the cached fitness
+    /// values of the selected individuals. This is used to migrate individuals between
+    /// populations without evaluating their fitness again.
+    ///
+    /// By default, this calls `select` and evaluates the fitness of the selected phenotypes
+    /// again. The selectors of this module return the cached values instead.
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        rng: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
+        Ok(self.select(population, rng)?
+            .into_iter()
+            .map(|(a, b)| (Evaluated::new(a), Evaluated::new(b)))
+            .collect())
+    }
+}
+
+/// Strip the cached fitness values from `parents`.
+fn phenotypes<T, F>(parents: EvaluatedParents<T, F>) -> Parents<T>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    parents.into_iter().map(|(a, b)| (a.into_pheno(), b.into_pheno())).collect()
 }
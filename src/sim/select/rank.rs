@@ -0,0 +1,228 @@
+// file: rank.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use super::*;
+use super::roulette::{cumulative, find_index};
+use rand::Rng;
+
+/// The way in which a `RankSelector` turns ranks into selection probabilities.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Ranking {
+    /// Linear ranking with the given selection pressure.
+    Linear(f64),
+    /// Exponential ranking with the given base.
+    Exponential(f64),
+}
+
+/// Selects phenotypes with a probability that depends on their rank in the population,
+/// rather than on the value of their fitness.
+///
+/// This is useful when the scale of the fitness function is meaningless, or when a single
+/// outlier would dominate fitness-proportionate selection.
+#[derive(Clone, Copy, Debug)]
+pub struct RankSelector {
+    count: usize,
+    ranking: Ranking,
+}
+
+impl RankSelector {
+    /// Create and return a linear ranking selector.
+    ///
+    /// The population is sorted by fitness, and the phenotype with rank `i` (the worst
+    /// phenotype has rank `0`, the best has rank `n - 1`) is selected with probability
+    /// `(2 - pressure + 2 * (pressure - 1) * i / (n - 1)) / n`. The best phenotype is thus
+    /// expected to be selected `pressure` times as often as an average phenotype.
+    /// In total, `count` parents are selected, with replacement.
+    ///
+    /// * `count`: must be larger than zero and a multiple of two.
+    /// * `pressure`: must be between `1.0` (no selection pressure) and `2.0`, inclusive.
+    pub fn linear(count: usize, pressure: f64) -> RankSelector {
+        RankSelector {
+            count,
+            ranking: Ranking::Linear(pressure),
+        }
+    }
+
+    /// Create and return an exponential ranking selector.
+    ///
+    /// The population is sorted by fitness, and the phenotype with rank `i` (the worst
+    /// phenotype has rank `0`, the best has rank `n - 1`) is selected with a probability
+    /// proportional to `base.powi(n - 1 - i)`. A smaller base results in a higher
+    /// selection pressure. In total, `count` parents are selected, with replacement.
+    ///
+    /// * `count`: must be larger than zero and a multiple of two.
+    /// * `base`: must be larger than `0.0` and at most `1.0`.
+    pub fn exponential(count: usize, base: f64) -> RankSelector {
+        RankSelector {
+            count,
+            ranking: Ranking::Exponential(base),
+        }
+    }
+
+    /// Compute the weight of every rank in a population of size `n`.
+    fn weights(&self, n: usize) -> Vec<f64> {
+        match self.ranking {
+            Ranking::Linear(pressure) => {
+                if n == 1 {
+                    return vec![1.0];
+                }
+                (0..n)
+                    .map(|i| 2.0 - pressure + 2.0 * (pressure - 1.0) * i as f64 / (n - 1) as f64)
+                    .collect()
+            }
+            Ranking::Exponential(base) => (0..n).map(|i| base.powi((n - 1 - i) as i32)).collect(),
+        }
+    }
+}
+
+impl<T, F> Selector<T, F> for RankSelector
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError> {
+        self.select_evaluated(population, rng).map(phenotypes)
+    }
+
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        rng: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
+        if self.count == 0 || self.count % 2 != 0 {
+            return Err(SimError::invalid_parameter("count",
+                                                   self.count,
+                                                   "Should be larger than zero and a multiple of \
+                                                    two."));
+        }
+        match self.ranking {
+            Ranking::Linear(pressure) if !(1.0..=2.0).contains(&pressure) => {
+                return Err(SimError::invalid_parameter("pressure",
+                                                       pressure,
+                                                       "Should be between 1 and 2, inclusive."));
+            }
+            Ranking::Exponential(base) if !(base > 0.0 && base <= 1.0) => {
+                return Err(SimError::invalid_parameter("base",
+                                                       base,
+                                                       "Should be larger than 0 and at most 1."));
+            }
+            _ => {}
+        }
+        if population.is_empty() {
+            return Err(SimError::PopulationTooSmall {
+                size: 0,
+                minimum: 1,
+            });
+        }
+
+        // Sort from worst to best, so that the index is the rank.
+        let mut sorted: Vec<&Evaluated<T, F>> = population.iter().collect();
+        sorted.sort_by(|x, y| x.fitness().cmp(y.fitness()));
+        let cumulative = cumulative(&self.weights(sorted.len()));
+        let total = cumulative[cumulative.len() - 1];
+
+        let mut pick = || {
+            let point = rng.gen::<f64>() * total;
+            sorted[find_index(&cumulative, point)].clone()
+        };
+        let mut result: EvaluatedParents<T, F> = Vec::with_capacity(self.count / 2);
+        for _ in 0..(self.count / 2) {
+            let a = pick();
+            let b = pick();
+            result.push((a, b));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::select::*;
+    use ::sim::{Evaluated, SimRng};
+    use test::{MyFitness, Test};
+
+    fn population(values: &[i64]) -> Vec<Evaluated<Test, MyFitness>> {
+        values.iter().map(|&f| Evaluated::new(Test { f })).collect()
+    }
+
+    fn count(parents: &Parents<Test>, f: i64) -> usize {
+        parents.iter()
+            .map(|&(a, b)| (a.f == f) as usize + (b.f == f) as usize)
+            .sum()
+    }
+
+    #[test]
+    fn test_count_zero() {
+        let selector = RankSelector::linear(0, 1.5);
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_count_odd() {
+        let selector = RankSelector::exponential(5, 0.5);
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_pressure() {
+        for &pressure in &[0.5, 2.5, f64::NAN] {
+            let selector = RankSelector::linear(2, pressure);
+            assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_base() {
+        for &base in &[0.0, -0.5, 1.5, f64::NAN] {
+            let selector = RankSelector::exponential(2, base);
+            assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_result_size() {
+        let selector = RankSelector::linear(20, 2.0);
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert_eq!(20, selector.select(&population, &mut SimRng::new(0)).unwrap().len() * 2);
+    }
+
+    #[test]
+    fn test_linear_ignores_scale() {
+        // With pressure 2, the worst phenotype is never selected,
+        // and the outlier is selected only twice as often as the middle one.
+        let selector = RankSelector::linear(3000, 2.0);
+        let parents = selector.select(&population(&[1000000, 1, 2]), &mut SimRng::new(4))
+            .unwrap();
+        assert_eq!(count(&parents, 1), 0);
+        // Expected: 1000 and 2000.
+        assert!(count(&parents, 2) > 900 && count(&parents, 2) < 1100);
+        assert!(count(&parents, 1000000) > 1900 && count(&parents, 1000000) < 2100);
+    }
+
+    #[test]
+    fn test_exponential() {
+        // Weights 0.25, 0.5 and 1.
+        let selector = RankSelector::exponential(3500, 0.5);
+        let parents = selector.select(&population(&[3, 1, 2]), &mut SimRng::new(5)).unwrap();
+        // Expected: 500, 1000 and 2000.
+        assert!(count(&parents, 1) > 400 && count(&parents, 1) < 600);
+        assert!(count(&parents, 2) > 900 && count(&parents, 2) < 1100);
+        assert!(count(&parents, 3) > 1900 && count(&parents, 3) < 2100);
+    }
+}
@@ -0,0 +1,273 @@
+// file: roulette.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use super::*;
+use rand::Rng;
+use std::fmt;
+
+/// Selects phenotypes with a probability proportional to their fitness.
+///
+/// Commonly known as *Roulette Wheel Selection* or *Fitness Proportionate Selection*.
+pub struct RouletteSelector<F: Fitness> {
+    count: usize,
+    weight: fn(&F) -> f64,
+    windowed: bool,
+}
+
+impl<F: Fitness> RouletteSelector<F> {
+    /// Create and return a roulette wheel selector.
+    ///
+    /// Such a selector converts the fitness of every phenotype to a weight using `weight`,
+    /// and selects `count` parents, each with a probability proportional to its weight.
+    /// Parents are selected with replacement.
+    ///
+    /// * `count`: must be larger than zero and a multiple of two.
+    /// * `weight`: must return a finite, non-negative number for every fitness value.
+    ///   At least one weight must be larger than zero.
+    ///
+    /// ```
+    /// use rsgenetic::sim::select::RouletteSelector;
+    ///
+    /// let selector: RouletteSelector<i32> = RouletteSelector::new(10, |f| f64::from(*f));
+    /// ```
+    pub fn new(count: usize, weight: fn(&F) -> f64) -> RouletteSelector<F> {
+        RouletteSelector {
+            count,
+            weight,
+            windowed: false,
+        }
+    }
+
+    /// Create and return a windowed roulette wheel selector.
+    ///
+    /// This selector behaves like the one returned by `new`, but subtracts the lowest weight
+    /// in the population from every weight before selecting. This makes it suitable for
+    /// fitness values that can be negative. The worst phenotypes are never selected, unless
+    /// all phenotypes have the same weight, which is an error.
+    ///
+    /// * `count`: must be larger than zero and a multiple of two.
+    /// * `weight`: must return a finite number for every fitness value.
+    pub fn windowed(count: usize, weight: fn(&F) -> f64) -> RouletteSelector<F> {
+        RouletteSelector {
+            count,
+            weight,
+            windowed: true,
+        }
+    }
+}
+
+impl<F: Fitness> Clone for RouletteSelector<F> {
+    fn clone(&self) -> RouletteSelector<F> {
+        *self
+    }
+}
+
+impl<F: Fitness> Copy for RouletteSelector<F> {}
+
+impl<F: Fitness> Debug for RouletteSelector<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RouletteSelector")
+            .field("count", &self.count)
+            .field("windowed", &self.windowed)
+            .finish()
+    }
+}
+
+/// Compute the weights of `population` using `weight`, optionally subtracting the
+/// lowest weight from all weights, and check that they can be used for sampling.
+pub(super) fn fitness_weights<T, F>(population: &[Evaluated<T, F>],
+                                    weight: fn(&F) -> f64,
+                                    windowed: bool)
+                                    -> Result<Vec<f64>, SimError>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    let mut weights: Vec<f64> = population.iter().map(|x| weight(x.fitness())).collect();
+    if let Some(w) = weights.iter().find(|w| !w.is_finite()) {
+        return Err(SimError::InvalidWeight {
+            weight: *w,
+            expected: "Weights should be finite numbers.",
+        });
+    }
+    if windowed {
+        let min = weights.iter().cloned().fold(f64::INFINITY, f64::min);
+        for w in &mut weights {
+            *w -= min;
+        }
+    }
+    if let Some(w) = weights.iter().find(|&&w| w < 0.0) {
+        return Err(SimError::InvalidWeight {
+            weight: *w,
+            expected: "Weights should not be negative.",
+        });
+    }
+    let total: f64 = weights.iter().sum();
+    if total <= 0.0 || !total.is_finite() {
+        return Err(SimError::InvalidWeight {
+            weight: total,
+            expected: "This is the sum of all weights. At least one weight should be larger \
+                       than zero, and the sum of all weights should be finite.",
+        });
+    }
+    Ok(weights)
+}
+
+/// Turn `weights` into a cumulative distribution.
+pub(super) fn cumulative(weights: &[f64]) -> Vec<f64> {
+    weights.iter()
+        .scan(0.0, |sum, w| {
+            *sum += w;
+            Some(*sum)
+        })
+        .collect()
+}
+
+/// Find the index of the first entry in `cumulative` that is larger than `point`.
+pub(super) fn find_index(cumulative: &[f64], point: f64) -> usize {
+    let index = cumulative.partition_point(|&c| c <= point);
+    if index < cumulative.len() {
+        return index;
+    }
+    // Rounding errors pushed `point` past the total. Take the last entry with a positive
+    // weight, which is the first one that reaches the total.
+    let total = cumulative[cumulative.len() - 1];
+    cumulative.partition_point(|&c| c < total)
+}
+
+impl<T, F> Selector<T, F> for RouletteSelector<F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError> {
+        self.select_evaluated(population, rng).map(phenotypes)
+    }
+
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        rng: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
+        if self.count == 0 || self.count % 2 != 0 {
+            return Err(SimError::invalid_parameter("count",
+                                                   self.count,
+                                                   "Should be larger than zero and a multiple of \
+                                                    two."));
+        }
+        let weights = fitness_weights(population, self.weight, self.windowed)?;
+        let cumulative = cumulative(&weights);
+        let total = cumulative[cumulative.len() - 1];
+
+        let mut pick = || {
+            let point = rng.gen::<f64>() * total;
+            population[find_index(&cumulative, point)].clone()
+        };
+        let mut result: EvaluatedParents<T, F> = Vec::with_capacity(self.count / 2);
+        for _ in 0..(self.count / 2) {
+            let a = pick();
+            let b = pick();
+            result.push((a, b));
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cumulative, find_index};
+    use ::sim::select::*;
+    use ::sim::{Evaluated, SimError, SimRng};
+    use test::{MyFitness, Test};
+
+    fn weight(f: &MyFitness) -> f64 {
+        f.f as f64
+    }
+
+    fn population(values: &[i64]) -> Vec<Evaluated<Test, MyFitness>> {
+        values.iter().map(|&f| Evaluated::new(Test { f })).collect()
+    }
+
+    #[test]
+    fn test_count_zero() {
+        let selector = RouletteSelector::new(0, weight);
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_count_odd() {
+        let selector = RouletteSelector::new(5, weight);
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_all_zero() {
+        let selector = RouletteSelector::new(2, weight);
+        assert!(selector.select(&population(&[0, 0, 0]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_invalid_weight() {
+        let selector: RouletteSelector<MyFitness> = RouletteSelector::new(2, |_| f64::NAN);
+        let result = selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0));
+        assert!(matches!(result, Err(SimError::InvalidWeight { .. })));
+        let selector: RouletteSelector<MyFitness> = RouletteSelector::new(2, |f| -(f.f as f64));
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_windowed_negative() {
+        let selector: RouletteSelector<MyFitness> =
+            RouletteSelector::windowed(20, |f| -(f.f as f64));
+        let parents = selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).unwrap();
+        // The phenotype with the highest fitness has the lowest weight: zero.
+        assert!(parents.iter().all(|&(a, b)| a.f != 3 && b.f != 3));
+    }
+
+    #[test]
+    fn test_result_size() {
+        let selector = RouletteSelector::new(20, weight);
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert_eq!(20, selector.select(&population, &mut SimRng::new(0)).unwrap().len() * 2);
+    }
+
+    #[test]
+    fn test_proportional() {
+        let selector = RouletteSelector::new(2000, weight);
+        let parents = selector.select(&population(&[0, 1, 3]), &mut SimRng::new(1)).unwrap();
+        let count = |f| {
+            parents.iter()
+                .map(|&(a, b)| (a.f == f) as usize + (b.f == f) as usize)
+                .sum::<usize>()
+        };
+        assert_eq!(count(0), 0);
+        // Expected: 500 and 1500.
+        assert!(count(1) > 400 && count(1) < 600);
+        assert!(count(3) > 1400 && count(3) < 1600);
+    }
+
+    #[test]
+    fn test_find_index_zero_weight_tail() {
+        let cumulative = cumulative(&[1.0, 2.0, 0.0, 0.0]);
+        assert_eq!(find_index(&cumulative, 0.5), 0);
+        assert_eq!(find_index(&cumulative, 1.0), 1);
+        // A point at or past the total never selects the trailing zero weights.
+        assert_eq!(find_index(&cumulative, 3.0), 1);
+        assert_eq!(find_index(&cumulative, 3.0 + 1e-12), 1);
+    }
+}
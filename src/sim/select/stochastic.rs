@@ -20,7 +20,9 @@ use rand::Rng;
 
 /// Selects phenotypes at random, starting from a random index and taking equidistant jumps.
 ///
-/// Commonly known as *Stochastic Universal Sampling*.
+/// This selector does not take fitness into account: which phenotypes are selected depends
+/// only on their position in the population. For fitness-weighted *Stochastic Universal
+/// Sampling*, use `SusSelector` instead.
 #[derive(Clone, Copy, Debug)]
 pub struct StochasticSelector {
     count: usize,
@@ -29,7 +31,7 @@ pub struct StochasticSelector {
 impl StochasticSelector {
     /// Create and return a stochastic selector.
     ///
-    /// Such a selector selects elements using stochastic universal sampling,
+    /// Such a selector selects elements at equidistant positions in the population,
     /// yielding parents with low, medium and high fitness values. In total,
     /// `count` parents are selected.
     ///
@@ -43,16 +45,27 @@ impl<T, F> Selector<T, F> for StochasticSelector
     where T: Phenotype<F>,
           F: Fitness
 {
-    fn select(&self, population: &[T]) -> Result<Parents<T>, String> {
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError> {
+        self.select_evaluated(population, rng).map(phenotypes)
+    }
+
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        rng: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
         if self.count == 0 || self.count % 2 != 0 || self.count >= population.len() {
-            return Err(format!("Invalid parameter `count`: {}. Should be larger than zero, a \
-                                multiple of two and less than the population size.",
-                               self.count));
+            return Err(SimError::invalid_parameter("count",
+                                                   self.count,
+                                                   "Should be larger than zero, a multiple of \
+                                                    two and less than the population size."));
         }
 
         let ratio = population.len() / self.count;
-        let mut result: Parents<T> = Vec::new();
-        let mut i = ::rand::thread_rng().gen_range::<usize>(0, population.len());
+        let mut result: EvaluatedParents<T, F> = Vec::new();
+        let mut i = rng.gen_range::<usize>(0, population.len());
         let mut selected = 0;
         while selected < self.count {
             result.push((population[i].clone(),
@@ -68,33 +81,38 @@ impl<T, F> Selector<T, F> for StochasticSelector
 #[cfg(test)]
 mod tests {
     use ::sim::select::*;
-    use test::Test;
+    use ::sim::{Evaluated, SimRng};
+    use test::{MyFitness, Test};
 
     #[test]
     fn test_count_zero() {
         let selector = StochasticSelector::new(0);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_count_odd() {
         let selector = StochasticSelector::new(5);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_count_too_large() {
         let selector = StochasticSelector::new(100);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_result_size() {
         let selector = StochasticSelector::new(20);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert_eq!(20, selector.select(&population).unwrap().len() * 2);
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert_eq!(20, selector.select(&population, &mut SimRng::new(0)).unwrap().len() * 2);
     }
 }
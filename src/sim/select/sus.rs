@@ -0,0 +1,197 @@
+// file: sus.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pheno::{Fitness, Phenotype};
+use super::*;
+use super::roulette::{cumulative, find_index, fitness_weights};
+use rand::Rng;
+use std::fmt;
+
+/// Selects phenotypes by laying equally spaced pointers over the cumulative
+/// fitness distribution.
+///
+/// Commonly known as *Stochastic Universal Sampling*. Unlike `StochasticSelector`,
+/// this selector takes fitness into account, and unlike `RouletteSelector`, the number of times
+/// a phenotype is selected never differs much from its expected value.
+pub struct SusSelector<F: Fitness> {
+    count: usize,
+    weight: fn(&F) -> f64,
+    windowed: bool,
+}
+
+impl<F: Fitness> SusSelector<F> {
+    /// Create and return a stochastic universal sampling selector.
+    ///
+    /// Such a selector converts the fitness of every phenotype to a weight using `weight`,
+    /// and places `count` equally spaced pointers over the cumulative weights, starting
+    /// at a random offset. The phenotypes under the pointers are selected, and paired in
+    /// a random order.
+    ///
+    /// * `count`: must be larger than zero and a multiple of two.
+    /// * `weight`: must return a finite, non-negative number for every fitness value.
+    ///   At least one weight must be larger than zero.
+    pub fn new(count: usize, weight: fn(&F) -> f64) -> SusSelector<F> {
+        SusSelector {
+            count,
+            weight,
+            windowed: false,
+        }
+    }
+
+    /// Create and return a windowed stochastic universal sampling selector.
+    ///
+    /// This selector behaves like the one returned by `new`, but subtracts the lowest weight
+    /// in the population from every weight before selecting, like
+    /// `RouletteSelector::windowed`.
+    ///
+    /// * `count`: must be larger than zero and a multiple of two.
+    /// * `weight`: must return a finite number for every fitness value.
+    pub fn windowed(count: usize, weight: fn(&F) -> f64) -> SusSelector<F> {
+        SusSelector {
+            count,
+            weight,
+            windowed: true,
+        }
+    }
+}
+
+impl<F: Fitness> Clone for SusSelector<F> {
+    fn clone(&self) -> SusSelector<F> {
+        *self
+    }
+}
+
+impl<F: Fitness> Copy for SusSelector<F> {}
+
+impl<F: Fitness> Debug for SusSelector<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SusSelector")
+            .field("count", &self.count)
+            .field("windowed", &self.windowed)
+            .finish()
+    }
+}
+
+impl<T, F> Selector<T, F> for SusSelector<F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError> {
+        self.select_evaluated(population, rng).map(phenotypes)
+    }
+
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        rng: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
+        if self.count == 0 || self.count % 2 != 0 {
+            return Err(SimError::invalid_parameter("count",
+                                                   self.count,
+                                                   "Should be larger than zero and a multiple of \
+                                                    two."));
+        }
+        let weights = fitness_weights(population, self.weight, self.windowed)?;
+        let cumulative = cumulative(&weights);
+        let total = cumulative[cumulative.len() - 1];
+
+        let distance = total / self.count as f64;
+        let start = rng.gen::<f64>() * distance;
+        let mut selected: Vec<usize> = (0..self.count)
+            .map(|i| find_index(&cumulative, start + i as f64 * distance))
+            .collect();
+        // The pointers select phenotypes in population order, so shuffle them
+        // to avoid pairing neighbours.
+        rng.shuffle(&mut selected);
+
+        Ok(selected.chunks(2)
+            .map(|pair| (population[pair[0]].clone(), population[pair[1]].clone()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::sim::select::*;
+    use ::sim::{Evaluated, SimRng};
+    use test::{MyFitness, Test};
+
+    fn weight(f: &MyFitness) -> f64 {
+        f.f as f64
+    }
+
+    fn population(values: &[i64]) -> Vec<Evaluated<Test, MyFitness>> {
+        values.iter().map(|&f| Evaluated::new(Test { f })).collect()
+    }
+
+    #[test]
+    fn test_count_zero() {
+        let selector = SusSelector::new(0, weight);
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_count_odd() {
+        let selector = SusSelector::new(5, weight);
+        assert!(selector.select(&population(&[1, 2, 3]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_all_zero() {
+        let selector = SusSelector::new(2, weight);
+        assert!(selector.select(&population(&[0, 0, 0]), &mut SimRng::new(0)).is_err());
+    }
+
+    #[test]
+    fn test_result_size() {
+        let selector = SusSelector::new(20, weight);
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert_eq!(20, selector.select(&population, &mut SimRng::new(0)).unwrap().len() * 2);
+    }
+
+    #[test]
+    fn test_exact_counts() {
+        // With 8 pointers over weights 1, 3 and 4, every phenotype is selected
+        // exactly as often as expected, regardless of the random offset.
+        let selector = SusSelector::new(8, weight);
+        for seed in 0..20 {
+            let parents = selector.select(&population(&[4, 1, 3]), &mut SimRng::new(seed))
+                .unwrap();
+            let count = |f| {
+                parents.iter()
+                    .map(|&(a, b)| (a.f == f) as usize + (b.f == f) as usize)
+                    .sum::<usize>()
+            };
+            assert_eq!((count(1), count(3), count(4)), (1, 3, 4));
+        }
+    }
+
+    #[test]
+    fn test_order_independent() {
+        let selector = SusSelector::new(8, weight);
+        for seed in 0..20 {
+            let parents = selector.select(&population(&[1, 3, 4]), &mut SimRng::new(seed))
+                .unwrap();
+            let count = parents.iter()
+                .map(|&(a, b)| (a.f == 4) as usize + (b.f == 4) as usize)
+                .sum::<usize>();
+            assert_eq!(count, 4);
+        }
+    }
+}
@@ -46,27 +46,38 @@ impl<T, F> Selector<T, F> for TournamentSelector
     where T: Phenotype<F>,
           F: Fitness
 {
-    fn select(&self, population: &[T]) -> Result<Parents<T>, String> {
+    fn select(&self,
+              population: &[Evaluated<T, F>],
+              rng: &mut SimRng)
+              -> Result<Parents<T>, SimError> {
+        self.select_evaluated(population, rng).map(phenotypes)
+    }
+
+    fn select_evaluated(&self,
+                        population: &[Evaluated<T, F>],
+                        rng: &mut SimRng)
+                        -> Result<EvaluatedParents<T, F>, SimError> {
         if self.count == 0 || self.count % 2 != 0 || self.count * 2 >= population.len() {
-            return Err(format!("Invalid parameter `count`: {}. Should be larger than zero, a \
-                                multiple of two and less than half the population size.",
-                               self.count));
+            return Err(SimError::invalid_parameter("count",
+                                                   self.count,
+                                                   "Should be larger than zero, a multiple of \
+                                                    two and less than half the population size."));
         }
         if self.participants == 0 || self.participants >= population.len() {
-            return Err(format!("Invalid parameter `participants`: {}. Should be larger than \
-                                zero and less than the population size.",
-                               self.participants));
+            return Err(SimError::invalid_parameter("participants",
+                                                   self.participants,
+                                                   "Should be larger than zero and less than the \
+                                                    population size."));
         }
 
-        let mut result: Parents<T> = Vec::new();
-        let mut rng = ::rand::thread_rng();
+        let mut result: EvaluatedParents<T, F> = Vec::new();
         for _ in 0..(self.count / 2) {
-            let mut tournament: Vec<T> = Vec::with_capacity(self.participants);
+            let mut tournament: Vec<&Evaluated<T, F>> = Vec::with_capacity(self.participants);
             for _ in 0..self.participants {
                 let index = rng.gen_range::<usize>(0, population.len());
-                tournament.push(population[index].clone());
+                tournament.push(&population[index]);
             }
-            tournament.sort_by(|x, y| y.fitness().cmp(&x.fitness()));
+            tournament.sort_by(|x, y| y.fitness().cmp(x.fitness()));
             result.push((tournament[0].clone(), tournament[1].clone()));
         }
         Ok(result)
@@ -76,47 +87,54 @@ impl<T, F> Selector<T, F> for TournamentSelector
 #[cfg(test)]
 mod tests {
     use ::sim::select::*;
-    use test::Test;
+    use ::sim::{Evaluated, SimRng};
+    use test::{MyFitness, Test};
 
     #[test]
     fn test_count_zero() {
         let selector = TournamentSelector::new(0, 1);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_participants_zero() {
         let selector = TournamentSelector::new(2, 0);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_count_odd() {
         let selector = TournamentSelector::new(5, 1);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_count_too_large() {
         let selector = TournamentSelector::new(100, 1);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_participants_too_large() {
         let selector = TournamentSelector::new(2, 100);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert!(selector.select(&population).is_err());
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert!(selector.select(&population, &mut SimRng::new(0)).is_err());
     }
 
     #[test]
     fn test_result_size() {
         let selector = TournamentSelector::new(20, 5);
-        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
-        assert_eq!(20, selector.select(&population).unwrap().len() * 2);
+        let population: Vec<Evaluated<Test, MyFitness>> =
+            (0..100).map(|i| Evaluated::new(Test { f: i })).collect();
+        assert_eq!(20, selector.select(&population, &mut SimRng::new(0)).unwrap().len() * 2);
     }
 }
@@ -18,32 +18,65 @@
 //! called a `Simulator`.
 //!
 //! To use a `Simulator`, you need a `SimulatorBuilder`, which you can
-//! obtain by calling `Simulator::builder()`.
+//! obtain by calling `Simulator::builder()`, or `Simulator::owned_builder()` to
+//! create a `Simulator` that owns its population.
 
 use pheno::Phenotype;
 use pheno::Fitness;
-use rand::Rng;
 use super::*;
 use super::select::*;
+use super::replace::*;
+use super::observer::*;
 use super::iterlimit::*;
 use super::earlystopper::*;
+use super::checkpoint::Checkpoint;
+use super::stop::*;
+use super::init::Initializer;
+use super::error::EMPTY_POPULATION;
+use rand::Rng;
+use std::mem;
 use std::time::Instant;
-use std::marker::PhantomData;
 
 /// A sequential implementation of `::sim::Simulation`.
 /// The genetic algorithm is run in a single thread.
+///
+/// The fitness of every individual is evaluated once, when it enters the population.
+/// While the `Simulator` is alive, the population is stored inside of it.
+/// When it is dropped, the final population is moved back into the `Vec`
+/// that was passed to `builder`.
+///
+/// A `Simulator` created with `owned_builder` does not borrow a `Vec`, so it can be
+/// stored in a struct, returned from a function or moved to another thread.
+/// Use `into_population` to get its final population.
 #[derive(Debug)]
 pub struct Simulator<'a, T, F>
     where T: 'a + Phenotype<F>,
           F: Fitness
 {
-    population: &'a mut Vec<T>,
+    /// The `Vec` that the population is moved back into when the `Simulator` is dropped,
+    /// or `None` if the `Simulator` owns its population.
+    target: Option<&'a mut Vec<T>>,
+    population: Vec<Evaluated<T, F>>,
     iter_limit: IterLimit,
-    selector: Box<Selector<T, F>>,
+    selector: Box<dyn Selector<T, F>>,
+    replacement: Box<dyn Replacement<T, F>>,
     earlystopper: Option<EarlyStopper<F>>,
+    stop_criterion: Option<Box<dyn StopCriterion<T, F>>>,
+    elitism: usize,
+    /// The probability that a child is created by crossover instead of by cloning a parent.
+    crossover_rate: f64,
+    /// The probability that a child is mutated.
+    mutation_rate: f64,
+    observers: Vec<Box<dyn Observer<T, F>>>,
+    /// The highest fitness reported to the observers.
+    best: Option<F>,
+    stop_reason: Option<StopReason>,
+    history: Vec<Stats<F>>,
+    /// The number of fitness evaluations performed so far.
+    evaluations: u64,
+    rng: SimRng,
     duration: Option<NanoSecond>,
-    error: Option<String>,
-    phantom: PhantomData<&'a T>,
+    error: Option<SimError>,
 }
 
 impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
@@ -54,72 +87,17 @@ impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
 
     /// Create builder.
     fn builder(population: &'a mut Vec<T>) -> SimulatorBuilder<'a, T, F> {
-        SimulatorBuilder {
-            sim: Simulator {
-                population,
-                iter_limit: IterLimit::new(100),
-                selector: Box::new(MaximizeSelector::new(3)),
-                earlystopper: None,
-                duration: Some(0),
-                error: None,
-                phantom: PhantomData::default(),
-            },
-        }
+        SimulatorBuilder::new(Some(population), Vec::new())
     }
 
     fn step(&mut self) -> StepResult {
-        if self.population.is_empty() {
-            self.error = Some("Tried to run a simulator without a population, or the \
-                                       population was empty."
-                .to_string());
-            return StepResult::Failure;
-        }
-        let time_start = Instant::now();
-        let should_stop = match self.earlystopper {
-            Some(ref x) => self.iter_limit.reached() || x.reached(),
-            None => self.iter_limit.reached(),
-        };
-        if should_stop {
-            return StepResult::Done;
-        } else {
-            // Perform selection
-            let parents = match self.selector.select(self.population) {
-                Ok(parents) => parents,
-                Err(e) => {
-                    self.error = Some(e);
-                    return StepResult::Failure;
-                }
-            };
-            // Create children from the selected parents and mutate them.
-            let mut children: Vec<T> = parents.iter()
-                .map(|&(ref a, ref b)| a.crossover(b))
-                .map(|c| c.mutate())
-                .collect();
-            // Kill off parts of the population at random to make room for the children
-            self.kill_off(children.len());
-            self.population.append(&mut children);
-
-            if let Some(ref mut stopper) = self.earlystopper {
-                let highest_fitness = self.population
-                    .iter()
-                    .max_by_key(|x| x.fitness())
-                    .unwrap()
-                    .fitness();
-                stopper.update(highest_fitness);
-            }
-
-            self.iter_limit.inc();
-        }
-        self.duration = match self.duration {
-            Some(x) => {
-                let elapsed = time_start.elapsed();
-                let y = elapsed.as_secs() as NanoSecond * 1_000_000_000 +
-                        i64::from(elapsed.subsec_nanos());
-                Some(x + y)
-            }
-            None => None,
-        };
-        StepResult::Success // Not done yet, but successful
+        self.step_with(|parents, variations| {
+            parents.iter()
+                .zip(variations)
+                .flat_map(|((a, b), variation)| variation.breed(a, b))
+                .map(Evaluated::new)
+                .collect()
+        })
     }
 
     #[allow(deprecated)]
@@ -143,10 +121,16 @@ impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
         }
     }
 
-    fn get(&'a self) -> SimResult<'a, T> {
+    fn get(&self) -> SimResult<'_, T> {
         match self.error {
             Some(ref e) => Err(e),
-            None => Ok(self.population.iter().max_by_key(|x| x.fitness()).unwrap()),
+            None => {
+                self.population
+                    .iter()
+                    .max_by(|x, y| x.fitness().cmp(y.fitness()))
+                    .map(|x| x.pheno())
+                    .ok_or(&EMPTY_POPULATION)
+            }
         }
     }
 
@@ -159,7 +143,15 @@ impl<'a, T, F> Simulation<'a, T, F> for Simulator<'a, T, F>
     }
 
     fn population(&self) -> Vec<T> {
-        self.population.clone()
+        self.population.iter().map(|x| x.pheno().clone()).collect()
+    }
+
+    fn history(&self) -> &[Stats<F>] {
+        &self.history
+    }
+
+    fn stop_reason(&self) -> Option<&StopReason> {
+        self.stop_reason.as_ref()
     }
 }
 
@@ -167,21 +159,292 @@ impl<'a, T, F> Simulator<'a, T, F>
     where T: Phenotype<F>,
           F: Fitness
 {
-    /// Kill off phenotypes using stochastic universal sampling.
-    fn kill_off(&mut self, count: usize) {
-        let ratio = self.population.len() / count;
-        let mut i = ::rand::thread_rng().gen_range::<usize>(0, self.population.len());
-        let mut selected = 0;
-        while selected < count {
-            self.population.remove(i);
-            i += ratio - 1;
-            i %= self.population.len();
+    /// Create a builder for a `Simulator` that owns `population`, instead of borrowing it.
+    ///
+    /// Use `into_population` to get the final population back. Of the other simulators,
+    /// only `par::Simulator` can own its population as well.
+    pub fn owned_builder(population: Vec<T>) -> SimulatorBuilder<'a, T, F> {
+        SimulatorBuilder::new(None, population)
+    }
+
+    /// Consume the `Simulator` and return its current population.
+    ///
+    /// If the `Simulator` was created with `builder`, the population is returned
+    /// instead of being moved back into the `Vec` that was passed to `builder`.
+    pub fn into_population(mut self) -> Vec<T> {
+        mem::take(&mut self.population).into_iter().map(Evaluated::into_pheno).collect()
+    }
+
+    /// Make one step in the simulation, using `breed` to create and evaluate
+    /// children from the selected parents, applying the variation of the same index
+    /// to every pair of parents.
+    ///
+    /// This allows other simulators, such as `par::Simulator`, to reuse the
+    /// logic of a step while changing how the expensive parts are executed.
+    pub(super) fn step_with<B>(&mut self, breed: B) -> StepResult
+        where B: FnOnce(&Parents<T>, &[Variation]) -> Vec<Evaluated<T, F>>
+    {
+        if self.population.is_empty() {
+            return self.fail(SimError::PopulationTooSmall {
+                size: 0,
+                minimum: 1,
+            });
+        }
+        if let Some(index) = self.population.iter().position(|x| !x.fitness().is_valid()) {
+            let error = SimError::invalid_fitness(index, self.population[index].fitness());
+            return self.fail(error);
+        }
+        let time_start = Instant::now();
+        let mut reason = limit_reached(&self.iter_limit, &self.earlystopper);
+        if let Some(ref mut criterion) = self.stop_criterion {
+            let state = StopState {
+                iteration: self.iter_limit.get(),
+                evaluations: self.evaluations,
+                time: self.duration.unwrap_or(0),
+                population: &self.population,
+                history: &self.history,
+            };
+            if criterion.should_stop(&state) {
+                reason = reason.or(Some(StopReason::Criterion));
+            }
+        }
+        if reason.is_none() && self.observers.iter().any(|o| o.stop_requested()) {
+            reason = Some(StopReason::Observer);
+        }
+        if let Some(reason) = reason {
+            self.notify_termination(reason);
+            return StepResult::Done;
+        } else {
+            let iteration = self.iter_limit.get();
+            for observer in &mut self.observers {
+                observer.on_generation_start(iteration, &self.population);
+            }
+            let observers = &mut self.observers;
+            let evaluations = &mut self.evaluations;
+            let (crossover_rate, mutation_rate) = (self.crossover_rate, self.mutation_rate);
+            let result = next_generation(&mut self.population,
+                                         &*self.selector,
+                                         &*self.replacement,
+                                         self.elitism,
+                                         &mut self.rng,
+                                         |parents, rng| {
+                for observer in observers.iter_mut() {
+                    observer.on_selection(iteration, parents);
+                }
+                let variations: Vec<Variation> = parents.iter()
+                    .map(|_| Variation::draw(crossover_rate, mutation_rate, rng))
+                    .collect();
+                let children = breed(parents, &variations);
+                *evaluations += children.len() as u64;
+                children
+            });
+            if let Err(e) = result {
+                return self.fail(e);
+            }
+
+            let best = self.population
+                .iter()
+                .max_by(|x, y| x.fitness().cmp(y.fitness()))
+                .unwrap();
+            if let Some(ref mut stopper) = self.earlystopper {
+                stopper.update(best.fitness().clone());
+            }
+            if self.best.as_ref().is_none_or(|b| best.fitness() > b) {
+                self.best = Some(best.fitness().clone());
+                for observer in &mut self.observers {
+                    observer.on_new_best(iteration, best);
+                }
+            }
+            self.history.extend(Stats::from_population(iteration,
+                                                       &self.population,
+                                                       elapsed_nanos(time_start)));
+            for observer in &mut self.observers {
+                observer.on_generation_end(iteration, &self.population);
+            }
+
+            self.iter_limit.inc();
+        }
+        self.duration = match self.duration {
+            Some(x) => {
+                let elapsed = time_start.elapsed();
+                let y = elapsed.as_secs() as NanoSecond * 1_000_000_000 +
+                        i64::from(elapsed.subsec_nanos());
+                Some(x + y)
+            }
+            None => None,
+        };
+        StepResult::Success // Not done yet, but successful
+    }
+
+    /// Record `error` and notify the observers that the simulation failed.
+    fn fail(&mut self, error: SimError) -> StepResult {
+        self.notify_termination(StopReason::Failure(error.clone()));
+        self.error = Some(error);
+        StepResult::Failure
+    }
+
+    /// Notify the observers that the simulation has terminated, unless they
+    /// have been notified before.
+    fn notify_termination(&mut self, reason: StopReason) {
+        if self.stop_reason.is_some() {
+            return;
+        }
+        let iteration = self.iter_limit.get();
+        for observer in &mut self.observers {
+            observer.on_termination(iteration, &reason);
+        }
+        self.stop_reason = Some(reason);
+    }
+
+    /// Capture the state of the simulation in a `Checkpoint`, from which it can be resumed
+    /// with `SimulatorBuilder::resume`.
+    pub fn checkpoint(&self) -> Checkpoint<T, F> {
+        Checkpoint {
+            population: self.population.clone(),
+            iterations: self.iter_limit.get(),
+            evaluations: self.evaluations,
+            earlystopper: self.earlystopper.clone(),
+            best: self.best.clone(),
+            history: self.history.clone(),
+            rng: self.rng,
+            duration: self.duration,
+        }
+    }
+
+    /// Returns whether an error occurred during a previous step.
+    pub(super) fn has_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+}
+
+/// How the children of a pair of parents are created.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Variation {
+    crossover: bool,
+    mutation_rate: f64,
+    /// Drives the genome operators and decides which children are mutated.
+    rng: SimRng,
+}
+
+impl Variation {
+    /// Decide how to create the children of a pair: crossover happens with probability
+    /// `crossover_rate`, and every child is mutated with probability `mutation_rate`.
+    ///
+    /// The children are created later, possibly on another thread, so every pair gets
+    /// a generator of its own drawn from `rng` to stay reproducible.
+    pub(super) fn draw(crossover_rate: f64, mutation_rate: f64, rng: &mut SimRng) -> Variation {
+        let crossover = crossover_rate >= 1.0 || rng.gen::<f64>() < crossover_rate;
+        Variation {
+            crossover,
+            mutation_rate,
+            rng: rng.gen(),
+        }
+    }
 
-            selected += 1;
+    /// Create the children of `a` and `b` with `Phenotype::crossover_many`.
+    /// Without crossover, the children are clones of `a` and `b` in turn, as many
+    /// as `Phenotype::num_children` says.
+    ///
+    /// The built-in genomes draw from the generator of this pair while breeding.
+    pub(super) fn breed<T, F>(mut self, a: &T, b: &T) -> Vec<T>
+        where T: Phenotype<F>,
+              F: Fitness
+    {
+        let previous = LocalRng::replace(Some(self.rng.gen()));
+        let children = if self.crossover {
+            let children = a.crossover_many(b);
+            debug_assert_eq!(children.len(), a.num_children(), "Wrong number of children.");
+            children
+        } else {
+            (0..a.num_children())
+                .map(|i| if i % 2 == 0 { a.clone() } else { b.clone() })
+                .collect()
+        };
+        let children = children.into_iter()
+            .map(|child| if self.mutates() { child.mutate() } else { child })
+            .collect();
+        LocalRng::replace(previous);
+        children
+    }
+
+    /// Decide whether the next child is mutated.
+    /// Rates of `0` and `1` do not draw a random number.
+    fn mutates(&mut self) -> bool {
+        if self.mutation_rate <= 0.0 || self.mutation_rate >= 1.0 {
+            self.mutation_rate >= 1.0
+        } else {
+            self.rng.gen::<f64>() < self.mutation_rate
         }
     }
 }
 
+/// Create the next generation of `population`: select parents with `selector`,
+/// use `breed` to create and evaluate children from them, and choose the survivors
+/// with `replacement`. `breed` may draw from `rng`.
+///
+/// This is shared by all simulators that evolve one or more populations like
+/// `Simulator` does.
+pub(super) fn next_generation<T, F, B>(population: &mut Vec<Evaluated<T, F>>,
+                                       selector: &dyn Selector<T, F>,
+                                       replacement: &dyn Replacement<T, F>,
+                                       elitism: usize,
+                                       rng: &mut SimRng,
+                                       breed: B)
+                                       -> Result<(), SimError>
+    where T: Phenotype<F>,
+          F: Fitness,
+          B: FnOnce(&Parents<T>, &mut SimRng) -> Vec<Evaluated<T, F>>
+{
+    if elitism > population.len() {
+        return Err(SimError::invalid_parameter("elitism",
+                                               elitism,
+                                               "The elite should not outnumber the population."));
+    }
+    // Perform selection
+    let parents = selector.select(population, rng)?;
+    // Create children from the selected parents and mutate them.
+    let children = breed(&parents, rng);
+    // Choose the survivors among the population and the children
+    replace(population, replacement, elitism, children, rng)
+}
+
+/// Replace `population` using `replacement` and `children`.
+///
+/// The `elitism` best phenotypes are kept out of replacement, so they always survive.
+fn replace<T, F>(population: &mut Vec<Evaluated<T, F>>,
+                 replacement: &dyn Replacement<T, F>,
+                 elitism: usize,
+                 children: Vec<Evaluated<T, F>>,
+                 rng: &mut SimRng)
+                 -> Result<(), SimError>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    let mut elite = take_elite(population, elitism);
+    *population = replacement.replace(mem::take(population), children, rng)?;
+    population.append(&mut elite);
+    Ok(())
+}
+
+/// Remove the `elitism` best phenotypes from `population` and return them.
+fn take_elite<T, F>(population: &mut Vec<Evaluated<T, F>>,
+                    elitism: usize)
+                    -> Vec<Evaluated<T, F>>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    if elitism == 0 {
+        return Vec::new();
+    }
+    let mut order: Vec<usize> = (0..population.len()).collect();
+    order.sort_by(|&a, &b| population[b].fitness().cmp(population[a].fitness()));
+    order.truncate(elitism);
+    // Remove from the back, so that the remaining indices stay valid.
+    order.sort_unstable_by(|a, b| b.cmp(a));
+    order.into_iter().map(|i| population.remove(i)).collect()
+}
+
 /// A `Builder` for the `Simulator` type.
 #[derive(Debug)]
 pub struct SimulatorBuilder<'a, T, F>
@@ -189,27 +452,77 @@ pub struct SimulatorBuilder<'a, T, F>
           F: Fitness
 {
     sim: Simulator<'a, T, F>,
+    /// The initial population of a `Simulator` that owns its population.
+    individuals: Vec<T>,
+    initializer: Option<(Box<dyn Initializer<T>>, usize)>,
+    checkpoint: Option<Checkpoint<T, F>>,
+    /// Whether the maximum number of iterations was set explicitly.
+    max_iters_set: bool,
 }
 
 impl<'a, T, F> SimulatorBuilder<'a, T, F>
     where T: Phenotype<F>,
           F: Fitness
 {
+    /// Create a builder with the default settings, for a `Simulator` that moves its
+    /// population back into `target`, if any, when it is dropped.
+    fn new(target: Option<&'a mut Vec<T>>, individuals: Vec<T>) -> SimulatorBuilder<'a, T, F> {
+        SimulatorBuilder {
+            sim: Simulator {
+                target,
+                population: Vec::new(),
+                iter_limit: IterLimit::new(100),
+                selector: Box::new(MaximizeSelector::new(3)),
+                replacement: Box::new(StochasticReplacement::new()),
+                earlystopper: None,
+                stop_criterion: None,
+                elitism: 0,
+                crossover_rate: 1.0,
+                mutation_rate: 1.0,
+                observers: Vec::new(),
+                best: None,
+                stop_reason: None,
+                history: Vec::new(),
+                evaluations: 0,
+                rng: SimRng::from_thread_rng(),
+                duration: Some(0),
+                error: None,
+            },
+            individuals,
+            initializer: None,
+            checkpoint: None,
+            max_iters_set: false,
+        }
+    }
+
     /// Set the selector of the resulting `Simulator`.
     ///
     /// Returns itself for chaining purposes.
-    pub fn set_selector(mut self, sel: Box<Selector<T, F>>) -> Self {
+    pub fn set_selector(mut self, sel: Box<dyn Selector<T, F>>) -> Self {
         self.sim.selector = sel;
         self
     }
 
+    /// Set the replacement strategy of the resulting `Simulator`, which chooses
+    /// the survivors among the population and the children of every step.
+    ///
+    /// By default, `StochasticReplacement` is used.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_replacement(mut self, replacement: Box<dyn Replacement<T, F>>) -> Self {
+        self.sim.replacement = replacement;
+        self
+    }
+
     /// Set the maximum number of iterations of the resulting `Simulator`.
     ///
-    /// The `Simulator` will stop running after this number of iterations.
+    /// The `Simulator` will stop running after this number of iterations. By default,
+    /// the maximum is 100 iterations, unless a stop criterion is set.
     ///
     /// Returns itself for chaining purposes.
     pub fn set_max_iters(mut self, i: u64) -> Self {
         self.sim.iter_limit = IterLimit::new(i);
+        self.max_iters_set = true;
         self
     }
 
@@ -221,6 +534,164 @@ impl<'a, T, F> SimulatorBuilder<'a, T, F>
         self.sim.earlystopper = Some(EarlyStopper::new(delta, n_iters));
         self
     }
+
+    /// Set a stopping criterion. The `Simulator` stops as soon as `criterion` says so.
+    /// Use the combinators of the `stop` module to combine several criteria.
+    ///
+    /// The default maximum of 100 iterations does not apply when a criterion is set.
+    /// Early stopping and a maximum number of iterations set with `set_max_iters` still
+    /// apply, in addition to `criterion`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_stop_criterion(mut self, criterion: Box<dyn StopCriterion<T, F>>) -> Self {
+        self.sim.stop_criterion = Some(criterion);
+        self
+    }
+
+    /// Set elitism. The `n` best phenotypes of the population are guaranteed to survive
+    /// every step, so the highest fitness in the population never decreases.
+    ///
+    /// The elite take no part in replacement: the replacement strategy only chooses
+    /// among the rest of the population and the children. By default, no elitism is used.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_elitism(mut self, n: usize) -> Self {
+        self.sim.elitism = n;
+        self
+    }
+
+    /// Set the probability that a pair of parents creates its children by crossover.
+    /// Otherwise, the children are clones of the parents: the first child is a clone
+    /// of the first parent, the second child of the second parent, and so on.
+    /// The rate is clamped to the range `[0, 1]`, and defaults to `1`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_crossover_rate(mut self, rate: f64) -> Self {
+        self.sim.crossover_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the probability that a child is mutated after crossover.
+    /// The rate is clamped to the range `[0, 1]`, and defaults to `1`.
+    ///
+    /// This decides whether `Phenotype::mutate` is called at all. The built-in genomes
+    /// have a mutation rate of their own in their configuration, which applies within
+    /// `mutate`, so the two rates multiply:
+    ///
+    /// * `BitString` and `RealVector` mutate every gene of a child with probability
+    ///   `mutation_rate` times the rate of the configuration.
+    /// * `Permutation` and `Tree` mutate a child with probability `mutation_rate` times
+    ///   the rate of the configuration, so only one of them should be set.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_mutation_rate(mut self, rate: f64) -> Self {
+        self.sim.mutation_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add an observer, which is notified of the progress of the resulting `Simulator`.
+    /// Observers are notified in the order in which they are added.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn add_observer(mut self, observer: Box<dyn Observer<T, F>>) -> Self {
+        self.sim.observers.push(observer);
+        self
+    }
+
+    /// Set the random number generator of the resulting `Simulator`.
+    ///
+    /// All randomness used by the `Simulator` and its `Selector` is drawn from
+    /// this generator. By default, a generator seeded from the thread-local
+    /// random number generator is used.
+    ///
+    /// To derive a `SimRng` from a generator of your own, use `rng.gen::<SimRng>()`.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_rng(mut self, rng: SimRng) -> Self {
+        self.sim.rng = rng;
+        self
+    }
+
+    /// Seed the random number generator of the resulting `Simulator`.
+    ///
+    /// Two simulations with the same seed, population and settings yield the same
+    /// results, as long as the `Phenotype` implementation is deterministic.
+    /// The built-in genomes draw their random numbers from this generator;
+    /// a `Phenotype` that uses `rand::thread_rng` is not reproducible.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.sim.rng = SimRng::new(seed);
+        self
+    }
+
+    /// Set an initializer, which creates `size` individuals that are added to
+    /// the population passed to `builder` when the `Simulator` is built.
+    ///
+    /// The initializer draws its randomness from the random number generator of the
+    /// `Simulator`, so seeding the `Simulator` also makes its initial population
+    /// reproducible. It is not used when resuming from a checkpoint.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn set_initializer(mut self, initializer: Box<dyn Initializer<T>>, size: usize) -> Self {
+        self.initializer = Some((initializer, size));
+        self
+    }
+
+    /// Resume the simulation captured in `checkpoint`.
+    ///
+    /// The resulting `Simulator` continues with the population, the iteration counter, the
+    /// random number generator and the statistics of the checkpoint. If the checkpoint
+    /// contains the state of early stopping, it replaces the one set with `set_early_stop`.
+    /// All other settings, such as the selector and the maximum number of iterations, are
+    /// taken from this builder, so they should be set like they were for the original
+    /// simulation. The population passed to `builder` is replaced by that of the checkpoint.
+    ///
+    /// Returns itself for chaining purposes.
+    pub fn resume(mut self, checkpoint: Checkpoint<T, F>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+}
+
+impl<'a, T, F> SimulatorBuilder<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Build the `Simulator`, using `evaluate` to compute the fitness of every
+    /// individual in the initial population. The returned fitness values must be
+    /// in the same order as the individuals.
+    pub(super) fn build_with<E>(mut self, evaluate: E) -> Simulator<'a, T, F>
+        where E: FnOnce(&[T]) -> Vec<F>
+    {
+        if self.sim.stop_criterion.is_some() && !self.max_iters_set {
+            self.sim.iter_limit = IterLimit::new(u64::MAX);
+        }
+        let mut individuals = mem::take(&mut self.individuals);
+        if let Some(ref mut target) = self.sim.target {
+            individuals.append(target);
+        }
+        if let Some(checkpoint) = self.checkpoint {
+            self.sim.population = checkpoint.population;
+            self.sim.iter_limit.set(checkpoint.iterations);
+            self.sim.evaluations = checkpoint.evaluations;
+            if checkpoint.earlystopper.is_some() {
+                self.sim.earlystopper = checkpoint.earlystopper;
+            }
+            self.sim.best = checkpoint.best;
+            self.sim.history = checkpoint.history;
+            self.sim.rng = checkpoint.rng;
+            self.sim.duration = checkpoint.duration;
+        } else {
+            if let Some((mut initializer, size)) = self.initializer {
+                individuals.append(&mut initializer.initialize(size, &mut self.sim.rng));
+            }
+            let fitnesses = evaluate(&individuals);
+            self.sim.evaluations = fitnesses.len() as u64;
+            self.sim.population = zip_evaluated(individuals, fitnesses);
+        }
+        self.sim
+    }
 }
 
 impl<'a, T, F> Builder<Simulator<'a, T, F>> for SimulatorBuilder<'a, T, F>
@@ -228,7 +699,19 @@ impl<'a, T, F> Builder<Simulator<'a, T, F>> for SimulatorBuilder<'a, T, F>
           F: Fitness
 {
     fn build(self) -> Simulator<'a, T, F> {
-        self.sim
+        self.build_with(evaluate_all)
+    }
+}
+
+impl<'a, T, F> Drop for Simulator<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn drop(&mut self) {
+        // Hand the final population back to the owner of `target`.
+        if let Some(ref mut target) = self.target {
+            target.extend(self.population.drain(..).map(Evaluated::into_pheno));
+        }
     }
 }
 
@@ -236,18 +719,540 @@ impl<'a, T, F> Builder<Simulator<'a, T, F>> for SimulatorBuilder<'a, T, F>
 mod tests {
     use ::sim::*;
     use ::sim::select::*;
+    use ::sim::replace::*;
+    use ::sim::observer::Observer;
+    use ::sim::select::Parents;
     use test::Test;
     use test::MyFitness;
+    use std::cell::Cell;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use rand::Rng;
+    use genome::{BitString, BitStringConfig};
+
+    thread_local!(static EVALUATIONS: Cell<usize> = const { Cell::new(0) });
+
+    /// A `Phenotype` that counts how often its fitness is evaluated.
+    #[derive(Clone, Copy)]
+    struct Counted {
+        f: i64,
+    }
+
+    impl Phenotype<MyFitness> for Counted {
+        fn fitness(&self) -> MyFitness {
+            EVALUATIONS.with(|e| e.set(e.get() + 1));
+            MyFitness { f: self.f }
+        }
+
+        fn crossover(&self, other: &Counted) -> Counted {
+            Counted { f: (self.f + other.f) / 2 }
+        }
+
+        fn mutate(&self) -> Counted {
+            Counted { f: self.f + 1 }
+        }
+    }
 
     #[test]
-    fn test_kill_off_count() {
-        let selector = MaximizeSelector::new(2);
+    fn test_fitness_evaluated_once() {
+        EVALUATIONS.with(|e| e.set(0));
+        let mut population: Vec<Counted> = (0..100).map(|i| Counted { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(10, 5)))
+            .set_early_stop(MyFitness { f: 0 }, 100)
+            .set_max_iters(20)
+            .build();
+        s.run();
+        s.get().unwrap();
+        // The initial population, plus 5 children in each of 20 iterations.
+        assert_eq!(EVALUATIONS.with(|e| e.get()), 100 + 20 * 5);
+    }
+
+    #[test]
+    fn test_population_returned_on_drop() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        {
+            let mut s = seq::Simulator::builder(&mut population)
+                .set_selector(Box::new(MaximizeSelector::new(10)))
+                .set_max_iters(5)
+                .build();
+            s.run();
+        }
+        assert_eq!(population.len(), 100);
+    }
+
+    fn evaluated(values: &[i64]) -> Vec<Evaluated<Test, MyFitness>> {
+        values.iter().map(|&f| Evaluated::new(Test { f })).collect()
+    }
+
+    #[test]
+    fn test_replace_count() {
+        let mut population = evaluated(&(0..100).collect::<Vec<i64>>());
+        let replacement = StochasticReplacement::new();
+        let children = evaluated(&[1000; 10]);
+        seq::replace(&mut population, &replacement, 0, children, &mut SimRng::new(0)).unwrap();
+        assert_eq!(population.len(), 100);
+        assert_eq!(population.iter().filter(|x| x.pheno().f == 1000).count(), 10);
+    }
+
+    #[test]
+    fn test_replace_keeps_elite() {
+        let mut population = evaluated(&(0..100).collect::<Vec<i64>>());
+        let replacement = StochasticReplacement::new();
+        let children = evaluated(&[0; 50]);
+        seq::replace(&mut population, &replacement, 5, children, &mut SimRng::new(0)).unwrap();
+        assert_eq!(population.len(), 100);
+        for f in 95..100 {
+            assert!(population.iter().any(|x| x.pheno().f == f));
+        }
+    }
+
+    #[test]
+    fn test_generational_keeps_elite() {
+        let mut population = evaluated(&(0..100).collect::<Vec<i64>>());
+        let replacement = GenerationalReplacement::new();
+        let children = evaluated(&[0; 98]);
+        seq::replace(&mut population, &replacement, 2, children, &mut SimRng::new(0)).unwrap();
+        let mut values: Vec<i64> = population.iter().map(|x| x.pheno().f).collect();
+        values.sort_unstable();
+        assert_eq!(values[..98], [0; 98]);
+        assert_eq!(values[98..], [98, 99]);
+    }
+
+    #[test]
+    fn test_replacement_error_propagate() {
         let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
         let mut s = seq::Simulator::builder(&mut population)
-            .set_selector(Box::new(selector))
+            .set_selector(Box::new(MaximizeSelector::new(20)))
+            .set_replacement(Box::new(MuCommaLambdaReplacement::new()))
+            .build();
+        assert_eq!(s.run(), RunResult::Failure);
+        assert_eq!(s.get().err(),
+                   Some(&SimError::NotEnoughChildren {
+                       children: 10,
+                       required: 100,
+                   }));
+    }
+
+    #[test]
+    fn test_plus_replacement_best_never_decreases() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(TournamentSelector::new(20, 5)))
+            .set_replacement(Box::new(MuPlusLambdaReplacement::new()))
+            .set_max_iters(50)
+            .set_seed(1)
+            .build();
+        let mut best = 99;
+        while let StepResult::Success = s.checked_step() {
+            let f = s.get().unwrap().f;
+            assert!(f >= best);
+            best = f;
+        }
+        assert_eq!(s.population().len(), 100);
+    }
+
+    #[test]
+    fn test_elitism_best_never_decreases() {
+        for seed in 0..10 {
+            let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+            let mut s = seq::Simulator::builder(&mut population)
+                .set_selector(Box::new(StochasticSelector::new(40)))
+                .set_elitism(1)
+                .set_max_iters(50)
+                .set_seed(seed)
+                .build();
+            let mut best = 99;
+            while let StepResult::Success = s.checked_step() {
+                let f = s.get().unwrap().f;
+                assert!(f >= best);
+                best = f;
+            }
+        }
+    }
+
+    #[test]
+    fn test_elitism_too_large() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(20)))
+            .set_elitism(95)
+            .build();
+        assert_eq!(s.run(), RunResult::Failure);
+    }
+
+    #[test]
+    fn test_invalid_fitness() {
+        let config = BitStringConfig::new(4, |b| if b.count_ones() == 4 {
+            types::StrictF64(f64::NAN)
+        } else {
+            types::StrictF64(1.0)
+        });
+        let mut population = vec![config.zeros(), config.zeros(), config.from_bits(&[true; 4])];
+        let mut s = seq::Simulator::builder(&mut population).build();
+        assert_eq!(s.run(), RunResult::Failure);
+        match s.get() {
+            Err(&SimError::InvalidFitness { index, value: Some(value) }) => {
+                assert_eq!(index, 2);
+                assert!(value.is_nan());
+            }
+            _ => panic!("Expected an invalid fitness."),
+        }
+    }
+
+    #[test]
+    fn test_get_empty_population() {
+        let mut population: Vec<Test> = Vec::new();
+        let s = seq::Simulator::builder(&mut population).build();
+        let error = SimError::PopulationTooSmall {
+            size: 0,
+            minimum: 1,
+        };
+        assert_eq!(s.get().err(), Some(&error));
+    }
+
+    #[test]
+    fn test_elitism_checked_before_breeding() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(20)))
+            .set_elitism(101)
+            .build();
+        assert_eq!(s.run(), RunResult::Failure);
+        assert!(matches!(s.get(), Err(&SimError::InvalidParameter { name: "elitism", .. })));
+        // No children were created and evaluated.
+        assert_eq!(s.checkpoint().evaluations, 100);
+    }
+
+    /// An `Observer` that records the callbacks it receives.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        events: Arc<Mutex<Vec<String>>>,
+        stop_after: Option<u64>,
+    }
+
+    impl Observer<Test, MyFitness> for Recorder {
+        fn on_generation_start(&mut self, i: u64, _: &[Evaluated<Test, MyFitness>]) {
+            self.events.lock().unwrap().push(format!("start {}", i));
+        }
+
+        fn on_selection(&mut self, i: u64, parents: &Parents<Test>) {
+            self.events.lock().unwrap().push(format!("select {} {}", i, parents.len()));
+        }
+
+        fn on_generation_end(&mut self, i: u64, _: &[Evaluated<Test, MyFitness>]) {
+            self.events.lock().unwrap().push(format!("end {}", i));
+        }
+
+        fn on_new_best(&mut self, i: u64, best: &Evaluated<Test, MyFitness>) {
+            self.events.lock().unwrap().push(format!("best {} {}", i, best.fitness().f));
+        }
+
+        fn on_termination(&mut self, i: u64, reason: &StopReason) {
+            self.events.lock().unwrap().push(format!("done {} {:?}", i, reason));
+        }
+
+        fn stop_requested(&self) -> bool {
+            let ends = self.events.lock().unwrap().iter().filter(|e| e.starts_with("end")).count();
+            self.stop_after.is_some_and(|n| ends as u64 >= n)
+        }
+    }
+
+    #[test]
+    fn test_observer_callbacks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut population: Vec<Test> = (0..10).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(2)))
+            .set_max_iters(2)
+            .set_elitism(1)
+            .add_observer(Box::new(Recorder { events: events.clone(), stop_after: None }))
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert_eq!(s.checked_step(), StepResult::Done);
+        // The best individual has fitness 9 and never improves.
+        assert_eq!(*events.lock().unwrap(),
+                   vec!["start 0", "select 0 1", "best 0 9", "end 0", "start 1", "select 1 1",
+                        "end 1", "done 2 MaxIterations"]);
+    }
+
+    #[test]
+    fn test_observer_stop() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut population: Vec<Test> = (0..10).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(2)))
+            .set_max_iters(100)
+            .add_observer(Box::new(Recorder { events: events.clone(), stop_after: Some(3) }))
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert_eq!(s.iterations(), 3);
+        assert_eq!(events.lock().unwrap().last().unwrap(), "done 3 Observer");
+        assert_eq!(s.stop_reason(), Some(&StopReason::Observer));
+    }
+
+    #[test]
+    fn test_observer_failure() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut population: Vec<Test> = (0..10).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(0)))
+            .add_observer(Box::new(Recorder { events: events.clone(), stop_after: None }))
             .build();
-        s.kill_off(10);
-        assert_eq!(s.population.len(), 90);
+        assert_eq!(s.run(), RunResult::Failure);
+        assert_eq!(events.lock().unwrap().len(), 2);
+        assert!(events.lock().unwrap()[1].starts_with("done 0 Failure"));
+        assert!(matches!(s.stop_reason(), Some(&StopReason::Failure(_))));
+    }
+
+    #[test]
+    fn test_history() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_max_iters(5)
+            .build();
+        assert!(s.history().is_empty());
+        s.run();
+        let history = s.history();
+        assert_eq!(history.len(), 5);
+        for (i, stats) in history.iter().enumerate() {
+            assert_eq!(stats.iteration, i as u64);
+            assert_eq!(stats.size, 100);
+            assert!(stats.worst <= stats.median && stats.median <= stats.best);
+            assert!(stats.diversity > 0.0 && stats.diversity <= 1.0);
+        }
+        assert!(history[4].best == s.get().unwrap().fitness());
+    }
+
+    fn resumable(population: &mut Vec<Test>) -> seq::SimulatorBuilder<'_, Test, MyFitness> {
+        seq::Simulator::builder(population)
+            .set_selector(Box::new(TournamentSelector::new(10, 4)))
+            .set_early_stop(MyFitness { f: 1 }, 50)
+            .set_max_iters(20)
+    }
+
+    #[test]
+    fn test_resume_same_result() {
+        let mut expected: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        resumable(&mut expected).set_seed(7).build().run();
+
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let checkpoint = {
+            let mut s = resumable(&mut population).set_seed(7).build();
+            for _ in 0..8 {
+                assert_eq!(s.checked_step(), StepResult::Success);
+            }
+            s.checkpoint()
+        };
+        assert_eq!(checkpoint.iterations(), 8);
+        let mut resumed = Vec::new();
+        {
+            let mut s = resumable(&mut resumed).resume(checkpoint).build();
+            assert_eq!(s.run(), RunResult::Done);
+            assert_eq!(s.iterations(), 20);
+            assert_eq!(s.history().len(), 20);
+        }
+        let values = |v: &[Test]| -> Vec<i64> { v.iter().map(|x| x.f).collect() };
+        assert_eq!(values(&resumed), values(&expected));
+    }
+
+    #[test]
+    fn test_stop_criterion() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_max_iters(1000)
+            .set_stop_criterion(Box::new(stop::any(vec![
+                Box::new(stop::MaxIterations::new(20)),
+                Box::new(stop::EvaluationBudget::new(130)),
+            ])))
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        // 100 initial evaluations and 5 children in every iteration.
+        assert_eq!(s.iterations(), 6);
+        assert_eq!(s.stop_reason(), Some(&StopReason::Criterion));
+    }
+
+    #[test]
+    fn test_stop_criterion_without_max_iters() {
+        let mut population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_stop_criterion(Box::new(stop::MaxIterations::new(150)))
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        // The default maximum of 100 iterations does not apply.
+        assert_eq!(s.iterations(), 150);
+        assert_eq!(s.stop_reason(), Some(&StopReason::Criterion));
+    }
+
+    /// Create a `Simulator` that owns its population.
+    fn owned(seed: u64) -> seq::Simulator<'static, Test, MyFitness> {
+        let population: Vec<Test> = (0..100).map(|i| Test { f: i }).collect();
+        seq::Simulator::owned_builder(population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_max_iters(10)
+            .set_seed(seed)
+            .build()
+    }
+
+    #[test]
+    fn test_owned_population() {
+        let mut s = owned(3);
+        assert_eq!(s.run(), RunResult::Done);
+        let best = s.get().unwrap().f;
+        let population = s.into_population();
+        assert_eq!(population.len(), 100);
+        assert!(population.iter().all(|x| x.f <= best));
+    }
+
+    #[test]
+    fn test_owned_other_thread() {
+        let mut s = owned(5);
+        let handle = thread::spawn(move || {
+            s.run();
+            s
+        });
+        let s = handle.join().unwrap();
+        assert_eq!(s.iterations(), 10);
+        let mut expected = owned(5);
+        expected.run();
+        let values = |v: Vec<Test>| -> Vec<i64> { v.into_iter().map(|x| x.f).collect() };
+        assert_eq!(values(s.into_population()), values(expected.into_population()));
+    }
+
+    #[test]
+    fn test_variation() {
+        let (a, b) = (Test { f: 5 }, Test { f: 3 });
+        let breed = |crossover, mutation_rate| {
+            let variation = seq::Variation {
+                crossover,
+                mutation_rate,
+                rng: SimRng::new(0),
+            };
+            variation.breed(&a, &b).iter().map(|x| x.f).collect::<Vec<i64>>()
+        };
+        assert_eq!(breed(false, 0.0), vec![5]);
+        assert_eq!(breed(true, 0.0), vec![3]);
+        assert_eq!(breed(false, 1.0), vec![4]);
+        assert_eq!(breed(true, 1.0), vec![2]);
+
+        let mut rng = SimRng::new(0);
+        let draws: Vec<seq::Variation> =
+            (0..1000).map(|_| seq::Variation::draw(0.0, 0.5, &mut rng)).collect();
+        assert!(draws.iter().all(|v| !v.crossover));
+        let mutated = draws.into_iter().filter(|&v| v.breed(&a, &b)[0].f == 4).count();
+        assert!(mutated > 400 && mutated < 600);
+        // Pairs get generators of their own.
+        let first = seq::Variation::draw(1.0, 0.0, &mut rng);
+        let second = seq::Variation::draw(1.0, 0.0, &mut rng);
+        assert!(first.crossover && second.crossover);
+        assert!(first.rng != second.rng);
+    }
+
+    #[test]
+    fn test_crossover_many() {
+        let config = BitStringConfig::new(16, |b| b.count_ones());
+        let population: Vec<BitString<usize>> = (0..20).map(|_| config.random()).collect();
+        let mut s = seq::Simulator::owned_builder(population)
+            .set_selector(Box::new(MaximizeSelector::new(4)))
+            .set_max_iters(10)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        // Every pair of parents creates two children, which are all evaluated.
+        assert_eq!(s.checkpoint().evaluations, 20 + 10 * 4);
+        assert_eq!(s.into_population().len(), 20);
+    }
+
+    #[test]
+    fn test_seed_reproducible() {
+        let config = BitStringConfig::new(64, |b| b.count_ones());
+        let mut rng = SimRng::new(7);
+        let population: Vec<BitString<usize>> =
+            (0..20).map(|_| config.random_with(&mut rng)).collect();
+        let run = || {
+            let mut s = seq::Simulator::owned_builder(population.clone())
+                .set_selector(Box::new(StochasticSelector::new(10)))
+                .set_crossover_rate(0.7)
+                .set_mutation_rate(0.5)
+                .set_max_iters(20)
+                .set_seed(3)
+                .build();
+            assert_eq!(s.run(), RunResult::Done);
+            s.into_population().iter().map(|x| x.words().to_vec()).collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_crossover_rate_keeps_offspring_count() {
+        let config = BitStringConfig::new(16, |b| b.count_ones());
+        let population: Vec<BitString<usize>> = (0..20).map(|_| config.random()).collect();
+        // Generational replacement fails unless all 9 pairs create both of their children.
+        let mut s = seq::Simulator::owned_builder(population)
+            .set_selector(Box::new(StochasticSelector::new(18)))
+            .set_replacement(Box::new(GenerationalReplacement::new()))
+            .set_elitism(2)
+            .set_crossover_rate(0.5)
+            .set_max_iters(20)
+            .set_seed(6)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        assert_eq!(s.checkpoint().evaluations, 20 + 20 * 18);
+        assert_eq!(s.into_population().len(), 20);
+    }
+
+    #[test]
+    fn test_mutation_rate() {
+        let population: Vec<Test> = (0..100).map(|i| Test { f: i * 10 }).collect();
+        let mut s: seq::Simulator<Test, MyFitness> = seq::Simulator::owned_builder(population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_crossover_rate(0.5)
+            .set_mutation_rate(0.0)
+            .set_max_iters(20)
+            .set_seed(4)
+            .build();
+        assert_eq!(s.run(), RunResult::Done);
+        // Crossover keeps the minimum of both parents, only mutation changes the values.
+        assert!(s.into_population().iter().all(|x| x.f % 10 == 0));
+    }
+
+    #[test]
+    fn test_mutation_rate_with_genome_rate() {
+        // The configuration flips every bit of a mutated child.
+        let config = BitStringConfig::new(16, |b| b.count_ones()).set_mutation_rate(1.0);
+        let run = |mutation_rate| {
+            let population: Vec<BitString<usize>> = (0..20).map(|_| config.zeros()).collect();
+            let mut s = seq::Simulator::owned_builder(population)
+                .set_selector(Box::new(StochasticSelector::new(10)))
+                .set_crossover_rate(0.0)
+                .set_mutation_rate(mutation_rate)
+                .set_max_iters(1)
+                .build();
+            assert_eq!(s.run(), RunResult::Done);
+            s.into_population().iter().map(|x| x.count_ones()).max().unwrap()
+        };
+        assert_eq!(run(0.0), 0);
+        assert_eq!(run(1.0), 16);
+    }
+
+    #[test]
+    fn test_initializer() {
+        let initial = |seed| {
+            let generate = |rng: &mut SimRng| Test { f: rng.gen_range(1, 1000) };
+            let known = vec![Test { f: 0 }];
+            let s: seq::Simulator<Test, MyFitness> = seq::Simulator::owned_builder(known)
+                .set_initializer(Box::new(init::FromFn::new(generate)), 99)
+                .set_seed(seed)
+                .build();
+            s.into_population()
+        };
+        let population = initial(3);
+        assert_eq!(population.len(), 100);
+        assert_eq!(population[0].f, 0);
+        let values = |v: Vec<Test>| -> Vec<i64> { v.into_iter().map(|x| x.f).collect() };
+        assert_eq!(values(population), values(initial(3)));
     }
 
     #[test]
@@ -258,8 +1263,10 @@ mod tests {
             .set_selector(Box::new(selector))
             .set_max_iters(2)
             .build();
+        assert!(s.stop_reason().is_none());
         s.run();
         assert!(s.iterations() <= 2);
+        assert_eq!(s.stop_reason(), Some(&StopReason::MaxIterations));
     }
 
     #[test]
@@ -273,6 +1280,7 @@ mod tests {
             .build();
         s.run();
         assert!(s.iterations() <= 5);
+        assert_eq!(s.stop_reason(), Some(&StopReason::EarlyStop));
     }
 
     #[test]
@@ -283,6 +1291,54 @@ mod tests {
             .set_selector(Box::new(selector))
             .build();
         s.run();
+        assert!(matches!(s.get(), Err(&SimError::InvalidParameter { name: "count", .. })));
+    }
+
+    #[test]
+    fn test_same_seed_same_result() {
+        let run = |seed| {
+            let mut population: Vec<Test> = (-50..50).map(|i| Test { f: i * 3 }).collect();
+            let mut s = seq::Simulator::builder(&mut population)
+                .set_selector(Box::new(TournamentSelector::new(10, 5)))
+                .set_early_stop(MyFitness { f: 1 }, 3)
+                .set_max_iters(50)
+                .set_seed(seed)
+                .build();
+            s.run();
+            let population: Vec<i64> = s.population().iter().map(|x| x.f).collect();
+            (s.get().unwrap().f, s.iterations(), population)
+        };
+        assert_eq!(run(7), run(7));
+    }
+
+    #[derive(Clone, Copy)]
+    struct Sqrt {
+        x: f64,
+    }
+
+    impl Phenotype<types::StrictF64> for Sqrt {
+        fn fitness(&self) -> types::StrictF64 {
+            types::StrictF64(self.x.sqrt())
+        }
+
+        fn crossover(&self, other: &Sqrt) -> Sqrt {
+            Sqrt { x: (self.x + other.x) / 2.0 }
+        }
+
+        fn mutate(&self) -> Sqrt {
+            Sqrt { x: self.x - 10.0 }
+        }
+    }
+
+    #[test]
+    fn test_invalid_fitness_fails() {
+        // The square root of a negative number is NaN.
+        let mut population: Vec<Sqrt> = (-1..99).map(|i| Sqrt { x: f64::from(i) }).collect();
+        let mut s = seq::Simulator::builder(&mut population)
+            .set_selector(Box::new(MaximizeSelector::new(10)))
+            .set_max_iters(100)
+            .build();
+        assert_eq!(s.run(), RunResult::Failure);
         assert!(s.get().is_err());
     }
 
@@ -0,0 +1,151 @@
+// file: stats.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics about the population of every generation of a `Simulation`.
+//!
+//! Simulators record a `Stats` value at the end of every generation.
+//! Use `Simulation::history` to get all of them, for example to plot convergence
+//! or to compare the performance of different settings.
+
+use pheno::{Fitness, Phenotype};
+use sim::{Evaluated, NanoSecond};
+
+/// Statistics about the population at the end of one generation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stats<F: Fitness> {
+    /// The index of the generation, starting at zero.
+    pub iteration: u64,
+    /// The highest fitness in the population.
+    pub best: F,
+    /// The lowest fitness in the population.
+    pub worst: F,
+    /// The median fitness of the population. For populations of even size,
+    /// this is the lower of both middle values.
+    pub median: F,
+    /// The mean fitness of the population, if the fitness type supports
+    /// conversion to a number through `Fitness::to_f64`.
+    pub mean: Option<f64>,
+    /// The number of individuals in the population.
+    pub size: usize,
+    /// An estimate of the diversity of the population: the number of distinct
+    /// fitness values, divided by the size of the population. This is `1.0` if all
+    /// individuals have a different fitness, and approaches `0.0` as the population
+    /// converges.
+    pub diversity: f64,
+    /// The wall-clock time spent on this generation.
+    pub time: NanoSecond,
+}
+
+impl<F: Fitness> Stats<F> {
+    /// Compute the statistics of `population`, for the generation with index `iteration`
+    /// that took `time` nanoseconds.
+    ///
+    /// Returns `None` if the population is empty.
+    pub fn from_population<'a, T, I>(iteration: u64,
+                                     population: I,
+                                     time: NanoSecond)
+                                     -> Option<Stats<F>>
+        where T: 'a + Phenotype<F>,
+              F: 'a,
+              I: IntoIterator<Item = &'a Evaluated<T, F>>
+    {
+        let mut fitnesses: Vec<&F> = population.into_iter().map(|x| x.fitness()).collect();
+        if fitnesses.is_empty() {
+            return None;
+        }
+        fitnesses.sort();
+        let size = fitnesses.len();
+        let mean = fitnesses.iter()
+            .map(|f| f.to_f64())
+            .sum::<Option<f64>>()
+            .map(|sum| sum / size as f64);
+        let distinct = 1 + fitnesses.windows(2).filter(|w| w[0] != w[1]).count();
+        Some(Stats {
+            iteration,
+            best: fitnesses[size - 1].clone(),
+            worst: fitnesses[0].clone(),
+            median: fitnesses[(size - 1) / 2].clone(),
+            mean,
+            size,
+            diversity: distinct as f64 / size as f64,
+            time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stats;
+    use pheno::Phenotype;
+    use sim::Evaluated;
+    use test::{MyFitness, Test};
+
+    #[test]
+    fn test_from_population() {
+        let population: Vec<Evaluated<Test, MyFitness>> = [3, 1, 4, 1, 5, -9]
+            .iter()
+            .map(|&f| Evaluated::new(Test { f }))
+            .collect();
+        let stats = Stats::from_population(7, &population, 100).unwrap();
+        assert_eq!(stats.iteration, 7);
+        assert_eq!(stats.best.f, 9);
+        assert_eq!(stats.worst.f, 1);
+        assert_eq!(stats.median.f, 3);
+        // `MyFitness` cannot be converted to a number.
+        assert_eq!(stats.mean, None);
+        assert_eq!(stats.size, 6);
+        assert!((stats.diversity - 5.0 / 6.0).abs() < 1e-9);
+        assert_eq!(stats.time, 100);
+    }
+
+    #[derive(Clone, Copy)]
+    struct Value {
+        v: i64,
+    }
+
+    impl Phenotype<i64> for Value {
+        fn fitness(&self) -> i64 {
+            self.v
+        }
+
+        fn crossover(&self, _: &Value) -> Value {
+            *self
+        }
+
+        fn mutate(&self) -> Value {
+            *self
+        }
+    }
+
+    #[test]
+    fn test_mean() {
+        let population: Vec<Evaluated<Value, i64>> = [1, 2, 6]
+            .iter()
+            .map(|&v| Evaluated::new(Value { v }))
+            .collect();
+        let stats = Stats::from_population(0, &population, 0).unwrap();
+        assert_eq!(stats.mean, Some(3.0));
+        assert_eq!(stats.median, 2);
+        assert_eq!(stats.diversity, 1.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let population: Vec<Evaluated<Value, i64>> = Vec::new();
+        assert!(Stats::from_population(0, &population, 0).is_none());
+    }
+}
@@ -0,0 +1,438 @@
+// file: stop.rs
+//
+// Copyright 2015-2016 The RsGenetic Developers
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! The stop module provides a trait that can be implemented to decide when a
+//! `Simulation` should stop, together with a couple of useful criteria and
+//! combinators to compose them.
+//!
+//! A criterion is set with `set_stop_criterion` on the `SimulatorBuilder` of `seq::Simulator`
+//! or `par::Simulator`. The island and NSGA-II simulators do not support criteria. A criterion
+//! is checked before every generation, and replaces the default maximum number of iterations,
+//! so the simulation runs until the criterion is met. Early stopping and a maximum number of
+//! iterations set explicitly with `set_max_iters` still apply.
+//!
+//! ```
+//! use rsgenetic::pheno::Phenotype;
+//! use rsgenetic::sim::stop::*;
+//!
+//! // Stop after 1000 iterations, or as soon as a fitness of 100 is reached.
+//! fn criterion<T: Phenotype<i32>>() -> Any<T, i32> {
+//!     any(vec![Box::new(MaxIterations::new(1000)), Box::new(TargetFitness::new(100))])
+//! }
+//! ```
+
+use pheno::{Fitness, Phenotype};
+use sim::{Evaluated, NanoSecond};
+use sim::stats::Stats;
+use std::fmt::{self, Debug};
+
+/// The state of a `Simulation` before a generation, which a `StopCriterion` uses to
+/// decide whether to stop.
+#[derive(Debug)]
+pub struct StopState<'a, T, F>
+    where T: 'a + Phenotype<F>,
+          F: 'a + Fitness
+{
+    /// The index of the next generation, which is the number of generations run so far.
+    pub iteration: u64,
+    /// The number of fitness evaluations performed so far, including those of the
+    /// initial population.
+    pub evaluations: u64,
+    /// The time spent running generations so far.
+    pub time: NanoSecond,
+    /// The current population.
+    pub population: &'a [Evaluated<T, F>],
+    /// The statistics of all generations run so far.
+    pub history: &'a [Stats<F>],
+}
+
+impl<'a, T, F> StopState<'a, T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Get the highest fitness in the population, or `None` if the population is empty.
+    pub fn best(&self) -> Option<&F> {
+        self.population.iter().map(|x| x.fitness()).max()
+    }
+}
+
+/// A `StopCriterion` decides when a `Simulation` should stop.
+pub trait StopCriterion<T, F>: Debug + Send
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    /// Returns whether the simulation should stop before running another generation.
+    ///
+    /// This is called once before every generation, so criteria may keep state
+    /// between calls.
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool;
+}
+
+/// Stops after a number of iterations.
+#[derive(Clone, Copy, Debug)]
+pub struct MaxIterations {
+    max: u64,
+}
+
+impl MaxIterations {
+    /// Create a criterion that stops after `max` iterations.
+    pub fn new(max: u64) -> MaxIterations {
+        MaxIterations { max }
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for MaxIterations
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        state.iteration >= self.max
+    }
+}
+
+/// Stops if the highest fitness stagnates.
+#[derive(Clone, Copy)]
+pub struct Stagnation<F: Fitness> {
+    delta: F,
+    generations: u64,
+}
+
+impl<F: Fitness> Stagnation<F> {
+    /// Create a criterion that stops if the highest fitness changed by less than `delta`
+    /// over the last `generations` generations.
+    ///
+    /// Unlike early stopping, this criterion only uses the statistics of the simulation,
+    /// so it keeps working when a simulation is resumed from a checkpoint.
+    pub fn new(delta: F, generations: u64) -> Stagnation<F> {
+        Stagnation { delta, generations }
+    }
+}
+
+impl<F: Fitness> Debug for Stagnation<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Stagnation")
+            .field("generations", &self.generations)
+            .finish()
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for Stagnation<F>
+    where T: Phenotype<F>,
+          F: Fitness + Send
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        let len = state.history.len() as u64;
+        if len <= self.generations {
+            return false;
+        }
+        let last = &state.history[state.history.len() - 1].best;
+        let before = &state.history[(len - 1 - self.generations) as usize].best;
+        last.abs_diff(before) < self.delta
+    }
+}
+
+/// Stops when the time spent running generations exceeds a budget.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeLimit {
+    budget: NanoSecond,
+}
+
+impl TimeLimit {
+    /// Create a criterion that stops when `budget` nanoseconds have been spent
+    /// running generations.
+    pub fn new(budget: NanoSecond) -> TimeLimit {
+        TimeLimit { budget }
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for TimeLimit
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        state.time >= self.budget
+    }
+}
+
+/// Stops when an individual reaches a target fitness.
+#[derive(Clone, Copy)]
+pub struct TargetFitness<F: Fitness> {
+    target: F,
+}
+
+impl<F: Fitness> TargetFitness<F> {
+    /// Create a criterion that stops when the highest fitness in the population
+    /// is at least `target`.
+    pub fn new(target: F) -> TargetFitness<F> {
+        TargetFitness { target }
+    }
+}
+
+impl<F: Fitness> Debug for TargetFitness<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TargetFitness").finish()
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for TargetFitness<F>
+    where T: Phenotype<F>,
+          F: Fitness + Send
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        state.best().is_some_and(|best| *best >= self.target)
+    }
+}
+
+/// Stops when the number of fitness evaluations exceeds a budget.
+#[derive(Clone, Copy, Debug)]
+pub struct EvaluationBudget {
+    budget: u64,
+}
+
+impl EvaluationBudget {
+    /// Create a criterion that stops when `budget` fitness evaluations have been performed.
+    ///
+    /// The budget is checked before every generation, so the final number of evaluations
+    /// can exceed it by the number of children of one generation.
+    pub fn new(budget: u64) -> EvaluationBudget {
+        EvaluationBudget { budget }
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for EvaluationBudget
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        state.evaluations >= self.budget
+    }
+}
+
+/// Stops when the diversity of the population collapses.
+#[derive(Clone, Copy, Debug)]
+pub struct DiversityCollapse {
+    threshold: f64,
+}
+
+impl DiversityCollapse {
+    /// Create a criterion that stops when the diversity of the population, as estimated by
+    /// `Stats::diversity`, drops to `threshold` or below.
+    ///
+    /// * `threshold`: should be between zero and one.
+    pub fn new(threshold: f64) -> DiversityCollapse {
+        DiversityCollapse { threshold }
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for DiversityCollapse
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        state.history.last().is_some_and(|stats| stats.diversity <= self.threshold)
+    }
+}
+
+/// Stops if any of its criteria says so. Created with `any`.
+pub struct Any<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    criteria: Vec<Box<dyn StopCriterion<T, F>>>,
+}
+
+/// Combine `criteria` into one criterion that stops if any of them says so.
+///
+/// All criteria are checked every time, so that stateful criteria stay up to date.
+pub fn any<T, F>(criteria: Vec<Box<dyn StopCriterion<T, F>>>) -> Any<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    Any { criteria }
+}
+
+impl<T, F> Debug for Any<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Any").field(&self.criteria).finish()
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for Any<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        let stops: Vec<bool> = self.criteria.iter_mut().map(|c| c.should_stop(state)).collect();
+        stops.into_iter().any(|stop| stop)
+    }
+}
+
+/// Stops if all of its criteria say so. Created with `all`.
+pub struct All<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    criteria: Vec<Box<dyn StopCriterion<T, F>>>,
+}
+
+/// Combine `criteria` into one criterion that stops if all of them say so.
+///
+/// All criteria are checked every time, so that stateful criteria stay up to date.
+pub fn all<T, F>(criteria: Vec<Box<dyn StopCriterion<T, F>>>) -> All<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    All { criteria }
+}
+
+impl<T, F> Debug for All<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("All").field(&self.criteria).finish()
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for All<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        let stops: Vec<bool> = self.criteria.iter_mut().map(|c| c.should_stop(state)).collect();
+        stops.into_iter().all(|stop| stop)
+    }
+}
+
+/// Stops if its criterion says not to. Created with `not`.
+pub struct Not<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    criterion: Box<dyn StopCriterion<T, F>>,
+}
+
+/// Invert `criterion`.
+pub fn not<T, F>(criterion: Box<dyn StopCriterion<T, F>>) -> Not<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    Not { criterion }
+}
+
+impl<T, F> Debug for Not<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Not").field(&self.criterion).finish()
+    }
+}
+
+impl<T, F> StopCriterion<T, F> for Not<T, F>
+    where T: Phenotype<F>,
+          F: Fitness
+{
+    fn should_stop(&mut self, state: &StopState<T, F>) -> bool {
+        !self.criterion.should_stop(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sim::Evaluated;
+    use sim::stats::Stats;
+    use test::{MyFitness, Test};
+
+    fn population(values: &[i64]) -> Vec<Evaluated<Test, MyFitness>> {
+        values.iter().map(|&f| Evaluated::new(Test { f })).collect()
+    }
+
+    fn history(bests: &[i64]) -> Vec<Stats<MyFitness>> {
+        bests.iter()
+            .enumerate()
+            .map(|(i, &f)| {
+                let mut stats = Stats::from_population(i as u64, &population(&[f, 0]), 0)
+                    .unwrap();
+                stats.diversity = 1.0 / (i + 1) as f64;
+                stats
+            })
+            .collect()
+    }
+
+    fn state<'a>(population: &'a [Evaluated<Test, MyFitness>],
+                 history: &'a [Stats<MyFitness>])
+                 -> StopState<'a, Test, MyFitness> {
+        StopState {
+            iteration: history.len() as u64,
+            evaluations: 10 * history.len() as u64,
+            time: 100,
+            population,
+            history,
+        }
+    }
+
+    #[test]
+    fn test_simple_criteria() {
+        let population = population(&[1, 5, 3]);
+        let history = history(&[1, 2, 3, 4]);
+        let state = state(&population, &history);
+        assert!(StopCriterion::should_stop(&mut MaxIterations::new(4), &state));
+        assert!(!StopCriterion::should_stop(&mut MaxIterations::new(5), &state));
+        assert!(StopCriterion::should_stop(&mut TimeLimit::new(100), &state));
+        assert!(!StopCriterion::should_stop(&mut TimeLimit::new(101), &state));
+        assert!(StopCriterion::should_stop(&mut EvaluationBudget::new(40), &state));
+        assert!(!StopCriterion::should_stop(&mut EvaluationBudget::new(41), &state));
+        assert!(TargetFitness::new(MyFitness { f: 5 }).should_stop(&state));
+        assert!(!TargetFitness::new(MyFitness { f: 6 }).should_stop(&state));
+        assert!(StopCriterion::should_stop(&mut DiversityCollapse::new(0.25), &state));
+        assert!(!StopCriterion::should_stop(&mut DiversityCollapse::new(0.2), &state));
+    }
+
+    #[test]
+    fn test_stagnation() {
+        let population = population(&[1]);
+        let history = history(&[1, 5, 6, 6]);
+        let state = state(&population, &history);
+        assert!(Stagnation::new(MyFitness { f: 1 }, 1).should_stop(&state));
+        assert!(!Stagnation::new(MyFitness { f: 1 }, 2).should_stop(&state));
+        assert!(Stagnation::new(MyFitness { f: 2 }, 2).should_stop(&state));
+        // Not enough generations to decide.
+        assert!(!Stagnation::new(MyFitness { f: 100 }, 4).should_stop(&state));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let population = population(&[1]);
+        let history = history(&[1, 2]);
+        let state = state(&population, &history);
+        let mut criterion = any(vec![Box::new(MaxIterations::new(10)),
+                                     Box::new(MaxIterations::new(2))]);
+        assert!(criterion.should_stop(&state));
+        let mut criterion = all(vec![Box::new(MaxIterations::new(10)),
+                                     Box::new(MaxIterations::new(2))]);
+        assert!(!criterion.should_stop(&state));
+        let mut criterion = not(Box::new(MaxIterations::new(10)));
+        assert!(criterion.should_stop(&state));
+        assert!(!any::<Test, MyFitness>(Vec::new()).should_stop(&state));
+        assert!(all::<Test, MyFitness>(Vec::new()).should_stop(&state));
+    }
+}
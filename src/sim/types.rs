@@ -31,8 +31,29 @@
 //! * `u32`
 //! * `u64`
 //! * `usize`
+//!
+//! Floating point numbers do not have a total ordering, because of `NaN`.
+//! This module provides wrappers around `f32` and `f64` that do implement
+//! `Fitness`, each with an explicit policy for `NaN` values:
+//!
+//! * `F32` and `F64` treat `NaN` as the worst possible fitness value.
+//!   It compares less than every other value, including negative infinity.
+//! * `StrictF32` and `StrictF64` order `NaN` in the same way, but consider it
+//!   an invalid fitness value: a simulation step fails as soon as an individual
+//!   with a `NaN` fitness is part of the population.
+//!
+//! ```
+//! use rsgenetic::sim::types::F64;
+//!
+//! assert!(F64(1.0) > F64(-2.5));
+//! assert!(F64(f64::NAN) < F64(f64::NEG_INFINITY));
+//! ```
+//!
+//! For multi-objective optimisation, `Objectives` wraps a `Vec<f64>` and
+//! implements `MultiFitness`.
 
-use pheno::Fitness;
+use pheno::{Fitness, MultiFitness};
+use std::cmp::Ordering;
 
 macro_rules! implement_fitness_int {
     ( $($t:ty),* ) => {
@@ -41,7 +62,7 @@ macro_rules! implement_fitness_int {
                 fn zero() -> $t {
                     0
                 }
-    
+
                 fn abs_diff(&self, other: &$t) -> $t {
                     if self > other {
                         self - other
@@ -49,9 +70,208 @@ macro_rules! implement_fitness_int {
                         other - self
                     }
                 }
+
+                fn to_f64(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
             }
         )*
     }
 }
 
 implement_fitness_int!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+macro_rules! implement_fitness_float {
+    ( $( $(#[$attr:meta])* $name:ident($t:ty), strict: $strict:expr; )* ) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Debug, Default)]
+            pub struct $name(pub $t);
+
+            impl $name {
+                /// Get the wrapped value.
+                pub fn value(&self) -> $t {
+                    self.0
+                }
+            }
+
+            impl From<$t> for $name {
+                fn from(value: $t) -> $name {
+                    $name(value)
+                }
+            }
+
+            impl PartialEq for $name {
+                fn eq(&self, other: &$name) -> bool {
+                    self.cmp(other) == Ordering::Equal
+                }
+            }
+
+            impl Eq for $name {}
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &$name) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                fn cmp(&self, other: &$name) -> Ordering {
+                    match (self.0.is_nan(), other.0.is_nan()) {
+                        (true, true) => Ordering::Equal,
+                        (true, false) => Ordering::Less,
+                        (false, true) => Ordering::Greater,
+                        (false, false) => self.0.partial_cmp(&other.0).unwrap(),
+                    }
+                }
+            }
+
+            impl Fitness for $name {
+                fn zero() -> $name {
+                    $name(0.0)
+                }
+
+                /// The difference between `NaN` and any other value is infinite,
+                /// the difference between two `NaN` values is zero.
+                fn abs_diff(&self, other: &$name) -> $name {
+                    match (self.0.is_nan(), other.0.is_nan()) {
+                        (true, true) => $name(0.0),
+                        (true, false) | (false, true) => $name(<$t>::INFINITY),
+                        (false, false) => $name((self.0 - other.0).abs()),
+                    }
+                }
+
+                fn is_valid(&self) -> bool {
+                    !($strict && self.0.is_nan())
+                }
+
+                fn to_f64(&self) -> Option<f64> {
+                    Some(f64::from(self.0))
+                }
+            }
+        )*
+    }
+}
+
+implement_fitness_float! {
+    /// An `f32` fitness value. `NaN` is treated as the worst possible fitness.
+    F32(f32), strict: false;
+    /// An `f64` fitness value. `NaN` is treated as the worst possible fitness.
+    F64(f64), strict: false;
+    /// An `f32` fitness value. `NaN` is an invalid fitness value, which makes a
+    /// simulation step fail.
+    StrictF32(f32), strict: true;
+    /// An `f64` fitness value. `NaN` is an invalid fitness value, which makes a
+    /// simulation step fail.
+    StrictF64(f64), strict: true;
+}
+
+/// A vector of objectives, all of which are maximised.
+///
+/// The ordering of `Objectives` is lexicographic, with `NaN` values treated
+/// as in `F64`. Pareto dominance is used for multi-objective selection instead,
+/// see `::sim::pareto`. Objectives that contain `NaN` are invalid, which makes a
+/// simulation step fail.
+#[derive(Clone, Debug, Default)]
+pub struct Objectives(pub Vec<f64>);
+
+impl PartialEq for Objectives {
+    fn eq(&self, other: &Objectives) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Objectives {}
+
+impl PartialOrd for Objectives {
+    fn partial_cmp(&self, other: &Objectives) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Objectives {
+    fn cmp(&self, other: &Objectives) -> Ordering {
+        let lhs = self.0.iter().map(|&x| F64(x));
+        let rhs = other.0.iter().map(|&x| F64(x));
+        lhs.cmp(rhs)
+    }
+}
+
+impl Fitness for Objectives {
+    fn zero() -> Objectives {
+        Objectives(Vec::new())
+    }
+
+    /// The element-wise absolute difference. Missing objectives are treated as zero.
+    fn abs_diff(&self, other: &Objectives) -> Objectives {
+        let len = self.0.len().max(other.0.len());
+        Objectives((0..len)
+            .map(|i| {
+                let a = F64(self.0.get(i).cloned().unwrap_or(0.0));
+                let b = F64(other.0.get(i).cloned().unwrap_or(0.0));
+                a.abs_diff(&b).value()
+            })
+            .collect())
+    }
+
+    fn is_valid(&self) -> bool {
+        !self.0.iter().any(|x| x.is_nan())
+    }
+}
+
+impl MultiFitness for Objectives {
+    fn num_objectives(&self) -> usize {
+        self.0.len()
+    }
+
+    fn objective(&self, index: usize) -> f64 {
+        self.0[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pheno::Fitness;
+
+    #[test]
+    fn test_float_ordering() {
+        let mut values: Vec<F64> = vec![F64(2.0), F64(f64::NAN), F64(-1.0), F64(f64::NEG_INFINITY)];
+        values.sort();
+        assert!(values[0].value().is_nan());
+        assert_eq!(values[1], F64(f64::NEG_INFINITY));
+        assert_eq!(values[2], F64(-1.0));
+        assert_eq!(values[3], F64(2.0));
+    }
+
+    #[test]
+    fn test_float_nan_equal() {
+        assert_eq!(F64(f64::NAN), F64(f64::NAN));
+        assert_eq!(F32(0.0), F32(-0.0));
+    }
+
+    #[test]
+    fn test_float_abs_diff() {
+        assert_eq!(F64(1.5).abs_diff(&F64(-1.0)), F64(2.5));
+        assert_eq!(F64(f64::NAN).abs_diff(&F64(1.0)), F64(f64::INFINITY));
+        assert_eq!(F64(f64::NAN).abs_diff(&F64(f64::NAN)), F64::zero());
+    }
+
+    #[test]
+    fn test_objectives_ordering() {
+        assert!(Objectives(vec![1.0, 0.0]) > Objectives(vec![0.0, 5.0]));
+        assert!(Objectives(vec![1.0, f64::NAN]) < Objectives(vec![1.0, 0.0]));
+        assert_eq!(Objectives(vec![1.0, 2.0]).abs_diff(&Objectives(vec![3.0])),
+                   Objectives(vec![2.0, 2.0]));
+    }
+
+    #[test]
+    fn test_float_validity() {
+        assert!(F64(f64::NAN).is_valid());
+        assert!(!StrictF64(f64::NAN).is_valid());
+        assert!(StrictF64(1.0).is_valid());
+        assert!(!StrictF32(f32::NAN).is_valid());
+        assert!(Objectives(vec![1.0, f64::INFINITY]).is_valid());
+        assert!(!Objectives(vec![1.0, f64::NAN]).is_valid());
+    }
+}
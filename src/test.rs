@@ -2,6 +2,7 @@ use pheno::*;
 use std::cmp;
 
 #[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MyFitness {
     pub f: i64,
 }
@@ -17,6 +18,7 @@ impl Fitness for MyFitness {
 }
 
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Test {
     pub f: i64,
 }